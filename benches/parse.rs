@@ -0,0 +1,63 @@
+//! parse long and deep expressions of growing size, the time per token should stay flat
+//!
+//! run with `cargo bench --bench parse`
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use tiny_expr_parser::{build_ast, MAX_DEPTH};
+
+/// the times which a expression is parsed, the fastest one is reported
+const ROUNDS: usize = 10;
+
+/// `1 + 2 * x - 3 + 2 * x - 3 ...`, a flat chain of `n` terms
+fn long(n: usize) -> String {
+    let mut code = String::from("1");
+    for _ in 1..n {
+        code.push_str(" + 2 * x - 3");
+    }
+    code
+}
+
+/// `1 + (1 + (...))`, nested `n` times, each of which is one level of `MAX_DEPTH`
+fn deep(n: usize) -> String {
+    let mut code = "1 + (".repeat(n);
+    code.push('1');
+    code.push_str(&")".repeat(n));
+    code
+}
+
+fn parse(code: &str) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(build_ast(black_box(code)).unwrap());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, sizes: &[usize], build: fn(usize) -> String) {
+    println!("{}", name);
+    println!(
+        "{:>10} {:>10} {:>12} {:>10}",
+        "size", "bytes", "time", "ns/byte"
+    );
+    for &n in sizes {
+        let code = build(n);
+        let time = parse(&code);
+        println!(
+            "{:>10} {:>10} {:>12?} {:>10.2}",
+            n,
+            code.len(),
+            time,
+            time.as_nanos() as f64 / code.len() as f64
+        );
+    }
+}
+
+fn main() {
+    report("long", &[500, 1000, 2000, 4000, 8000], long);
+    report("deep", &[32, 64, 128, MAX_DEPTH], deep);
+}
@@ -0,0 +1,45 @@
+//! tokenize formulas of growing length, the time per byte should stay flat
+//!
+//! run with `cargo bench --bench tokenize`
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use tiny_expr_parser::Tokenizer;
+
+/// the times which a formula is tokenized, the fastest one is reported
+const ROUNDS: usize = 10;
+
+/// a formula of about `len` bytes
+fn formula(len: usize) -> String {
+    const TERM: &str = "(price * 1.5e2 + 0xFF_FF) // qty - max(a, b ** 2) % 7 + ";
+    let mut code = TERM.repeat(len / TERM.len());
+    code.push('1');
+    code
+}
+
+fn tokenize(code: &str) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            let tokens: Result<Vec<_>, _> = Tokenizer::new(black_box(code)).collect();
+            black_box(tokens.unwrap());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    println!("{:>10} {:>12} {:>10}", "bytes", "time", "ns/byte");
+    for kb in [10, 20, 40, 80, 160, 320] {
+        let code = formula(kb * 1024);
+        let time = tokenize(&code);
+        println!(
+            "{:>10} {:>12?} {:>10.2}",
+            code.len(),
+            time,
+            time.as_nanos() as f64 / code.len() as f64
+        );
+    }
+}
@@ -0,0 +1,792 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, BitAnd, BitOr, BitXor, Mul, Neg, Not, Shl, Shr, Sub};
+use std::str::FromStr;
+
+/// the base of a limb, the largest power of ten which fits in `u32`
+const BASE: u64 = 1_000_000_000;
+/// the number of decimal digits in a limb
+const BASE_DIGITS: usize = 9;
+/// `2^32` in limbs, to convert the magnitude into binary for the bitwise operators
+const BINARY_BASE: [u32; 2] = [294_967_296, 4];
+
+/// arbitrary-precision signed integer
+///
+/// the magnitude is stored as little-endian limbs in base 10^9,
+/// which keeps the conversion from and to the decimal string trivial
+///
+/// # Example
+/// ```rust
+/// let a: BigInt = "99999999999999999999".parse().unwrap();
+/// let b = BigInt::from(2);
+/// assert_eq!("199999999999999999998", (a * b).to_string());
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    /// always `false` for zero
+    negative: bool,
+    /// without leading zero limbs, empty for zero
+    limbs: Vec<u32>,
+}
+
+/// the error returned when a string is not a decimal integer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid decimal integer")
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+impl BigInt {
+    /// the most bits which the result of a power or a left shift may have,
+    /// a larger one is rejected before it is computed, e.g. `2 ** 4000000000` or `1 << 4000000000`
+    pub const MAX_BITS: u64 = 1 << 16;
+
+    pub fn zero() -> Self {
+        BigInt::default()
+    }
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+    pub fn abs(mut self) -> Self {
+        self.negative = false;
+        self
+    }
+
+    /// the quotient truncated toward zero, or `None` if `rhs` is zero
+    pub fn checked_div(&self, rhs: &BigInt) -> Option<BigInt> {
+        self.checked_div_rem(rhs).map(|(quotient, _)| quotient)
+    }
+    /// the remainder which has the same sign as `self`, or `None` if `rhs` is zero
+    pub fn checked_rem(&self, rhs: &BigInt) -> Option<BigInt> {
+        self.checked_div_rem(rhs).map(|(_, remainder)| remainder)
+    }
+    /// the truncated quotient and the remainder, or `None` if `rhs` is zero
+    pub fn checked_div_rem(&self, rhs: &BigInt) -> Option<(BigInt, BigInt)> {
+        if rhs.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = div_rem_mag(&self.limbs, &rhs.limbs);
+        Some((
+            BigInt::from_parts(self.negative != rhs.negative, quotient),
+            BigInt::from_parts(self.negative, remainder),
+        ))
+    }
+
+    /// parse the digits in the `radix` between 2 and 16, e.g. `("ff", 16)`, without a sign or a prefix
+    ///
+    /// the digits are taken in chunks which fit in `u32`, e.g. 7 hexadecimal ones,
+    /// so the magnitude is multiplied once for every chunk rather than every digit
+    pub fn from_str_radix(digits: &str, radix: u32) -> Result<Self, ParseBigIntError> {
+        if digits.is_empty() || !(2..=16).contains(&radix) {
+            return Err(ParseBigIntError);
+        }
+        let chunk_len = (1..)
+            .take_while(|&len| radix.checked_pow(len).is_some())
+            .last()
+            .unwrap() as usize;
+        let mut limbs = Vec::new();
+        for chunk in digits.as_bytes().chunks(chunk_len) {
+            let (factor, value) = chunk.iter().try_fold((1, 0), |(factor, value), byte| {
+                let digit = (*byte as char).to_digit(radix).ok_or(ParseBigIntError)?;
+                Ok((factor * radix, value * radix + digit))
+            })?;
+            mul_add_small(&mut limbs, factor, value);
+        }
+        Ok(BigInt::from_parts(false, limbs))
+    }
+    /// the digits in the `radix` between 2 and 16 with the uppercase letters, e.g. `FF` for `(255, 16)`
+    ///
+    /// # Panics
+    ///
+    /// panic if the `radix` is not between 2 and 16
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!(
+            (2..=16).contains(&radix),
+            "radix must be between 2 and 16, but got {}",
+            radix
+        );
+        let base = BigInt::from(radix as i64);
+        let mut digits = Vec::new();
+        let mut rest = self.clone().abs();
+        while !rest.is_zero() {
+            let (quotient, remainder) = rest.checked_div_rem(&base).unwrap();
+            let digit = remainder.limbs.first().copied().unwrap_or(0);
+            digits.push(
+                std::char::from_digit(digit, radix)
+                    .unwrap()
+                    .to_ascii_uppercase(),
+            );
+            rest = quotient;
+        }
+        if digits.is_empty() {
+            digits.push('0');
+        }
+        if self.negative {
+            digits.push('-');
+        }
+        digits.iter().rev().collect()
+    }
+
+    /// a lower bound of `log2(|self|)`, `0` for zero and one,
+    /// to estimate the size of a result before computing it
+    pub(crate) fn log2_lower(&self) -> u64 {
+        match self.limbs.split_last() {
+            // every lower limb contributes `log2(10^9)`, which is more than 29 bits
+            Some((top, rest)) => rest.len() as u64 * 29 + (31 - top.leading_zeros()) as u64,
+            None => 0,
+        }
+    }
+
+    /// shift left like `<<`, or `None` if the result would have more than `MAX_BITS` bits
+    pub fn checked_shl(self, amount: u32) -> Option<BigInt> {
+        match self.log2_lower() + amount as u64 <= BigInt::MAX_BITS {
+            true => Some(self << amount),
+            false => None,
+        }
+    }
+
+    /// the value as `u32` if it fits, e.g. the amount of a shift
+    pub fn to_u32(&self) -> Option<u32> {
+        match (self.negative, self.limbs.as_slice()) {
+            (true, _) => None,
+            (false, []) => Some(0),
+            (false, [low]) => Some(*low),
+            (false, [low, high]) => u32::try_from(*high as u64 * BASE + *low as u64).ok(),
+            _ => None,
+        }
+    }
+
+    /// the value as `i128` if it fits, which the primitive integers are converted from
+    pub fn to_i128(&self) -> Option<i128> {
+        self.limbs.iter().rev().try_fold(0_i128, |acc, limb| {
+            let acc = acc.checked_mul(BASE as i128)?;
+            match self.negative {
+                true => acc.checked_sub(*limb as i128),
+                false => acc.checked_add(*limb as i128),
+            }
+        })
+    }
+
+    /// the value as `u128` if it fits, e.g. the bits of a radix literal
+    pub fn to_u128(&self) -> Option<u128> {
+        match self.negative {
+            true => None,
+            false => self.limbs.iter().rev().try_fold(0_u128, |acc, limb| {
+                acc.checked_mul(BASE as u128)?.checked_add(*limb as u128)
+            }),
+        }
+    }
+
+    /// the two's complement in little-endian binary limbs,
+    /// the highest bit of the last limb is the sign
+    fn to_binary(&self) -> Vec<u32> {
+        let mut binary = Vec::new();
+        let mut rest = self.limbs.clone();
+        while !rest.is_empty() {
+            let (quotient, remainder) = div_rem_mag(&rest, &BINARY_BASE);
+            let low = remainder.first().copied().unwrap_or(0) as u64;
+            let high = remainder.get(1).copied().unwrap_or(0) as u64;
+            binary.push((high * BASE + low) as u32);
+            rest = trim(quotient);
+        }
+        // leave room for the sign bit
+        if binary.last().is_some_and(|limb| limb >> 31 == 1) || binary.is_empty() {
+            binary.push(0);
+        }
+        match self.negative {
+            true => negate_binary(binary),
+            false => binary,
+        }
+    }
+    /// build from the two's complement in little-endian binary limbs
+    fn from_binary(binary: Vec<u32>) -> Self {
+        let negative = binary.last().is_some_and(|limb| limb >> 31 == 1);
+        let magnitude = match negative {
+            true => negate_binary(binary),
+            false => binary,
+        };
+        let base = BigInt::from(1_i64 << 32);
+        let value = magnitude
+            .into_iter()
+            .rev()
+            .fold(BigInt::zero(), |acc, limb| {
+                acc * base.clone() + BigInt::from(limb as i64)
+            });
+        match negative {
+            true => -value,
+            false => value,
+        }
+    }
+    /// apply `op` to every pair of binary limbs, the shorter one is extended with its sign
+    fn bitwise(self, rhs: BigInt, op: impl Fn(u32, u32) -> u32) -> BigInt {
+        let (mut a, mut b) = (self.to_binary(), rhs.to_binary());
+        let len = a.len().max(b.len());
+        sign_extend(&mut a, len);
+        sign_extend(&mut b, len);
+        BigInt::from_binary(a.into_iter().zip(b).map(|(x, y)| op(x, y)).collect())
+    }
+
+    /// build from the sign and the magnitude, keeping the invariants
+    fn from_parts(negative: bool, limbs: Vec<u32>) -> Self {
+        let limbs = trim(limbs);
+        BigInt {
+            negative: negative && !limbs.is_empty(),
+            limbs,
+        }
+    }
+}
+
+impl From<i32> for BigInt {
+    fn from(value: i32) -> Self {
+        BigInt::from(value as i128)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        BigInt::from(value as i128)
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> Self {
+        let mut magnitude = value.unsigned_abs();
+        let mut limbs = Vec::new();
+        while magnitude > 0 {
+            limbs.push((magnitude % BASE as u128) as u32);
+            magnitude /= BASE as u128;
+        }
+        BigInt::from_parts(value < 0, limbs)
+    }
+}
+
+/// accept an optional sign followed by decimal digits, e.g. `-123`
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+        // every limb takes 9 digits from the end
+        let limbs = digits
+            .as_bytes()
+            .rchunks(BASE_DIGITS)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0, |limb, digit| limb * 10 + (digit - b'0') as u32)
+            })
+            .collect();
+        Ok(BigInt::from_parts(negative, limbs))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut digits = String::new();
+        match self.limbs.split_last() {
+            None => digits.push('0'),
+            Some((most, rest)) => {
+                digits.push_str(&most.to_string());
+                for limb in rest.iter().rev() {
+                    digits.push_str(&format!("{:09}", limb));
+                }
+            }
+        }
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.limbs, &other.limbs),
+            (true, true) => cmp_mag(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.limbs)
+    }
+}
+
+impl Add for BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(self.negative, add_mag(&self.limbs, &rhs.limbs));
+        }
+        // the signs differ, subtract the smaller magnitude from the larger one
+        match cmp_mag(&self.limbs, &rhs.limbs) {
+            Ordering::Less => BigInt::from_parts(rhs.negative, sub_mag(&rhs.limbs, &self.limbs)),
+            _ => BigInt::from_parts(self.negative, sub_mag(&self.limbs, &rhs.limbs)),
+        }
+    }
+}
+
+impl Sub for BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: BigInt) -> BigInt {
+        self + -rhs
+    }
+}
+
+impl Mul for BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != rhs.negative,
+            mul_mag(&self.limbs, &rhs.limbs),
+        )
+    }
+}
+
+/// the bitwise operators behave as if the value were stored in two's complement with infinite bits,
+/// e.g. `-1 & 5` is `5`
+impl BitAnd for BigInt {
+    type Output = BigInt;
+
+    fn bitand(self, rhs: BigInt) -> BigInt {
+        self.bitwise(rhs, |x, y| x & y)
+    }
+}
+
+impl BitOr for BigInt {
+    type Output = BigInt;
+
+    fn bitor(self, rhs: BigInt) -> BigInt {
+        self.bitwise(rhs, |x, y| x | y)
+    }
+}
+
+impl BitXor for BigInt {
+    type Output = BigInt;
+
+    fn bitxor(self, rhs: BigInt) -> BigInt {
+        self.bitwise(rhs, |x, y| x ^ y)
+    }
+}
+
+/// `!x` is `-x - 1`
+impl Not for BigInt {
+    type Output = BigInt;
+
+    fn not(self) -> BigInt {
+        -self - BigInt::from(1)
+    }
+}
+
+/// no bit is ever shifted out, e.g. `1 << 100` is `2^100`,
+/// the result is not limited, use `checked_shl` for an untrusted amount
+impl Shl<u32> for BigInt {
+    type Output = BigInt;
+
+    fn shl(self, amount: u32) -> BigInt {
+        let mut binary = self.to_binary();
+        let sign = sign_limb(&binary);
+        binary.push(sign);
+        let (limbs, bits) = ((amount / 32) as usize, amount % 32);
+        if bits > 0 {
+            for idx in (0..binary.len()).rev() {
+                let carry = if idx > 0 {
+                    binary[idx - 1] >> (32 - bits)
+                } else {
+                    0
+                };
+                binary[idx] = (binary[idx] << bits) | carry;
+            }
+        }
+        binary.splice(0..0, std::iter::repeat_n(0, limbs));
+        BigInt::from_binary(binary)
+    }
+}
+
+/// the arithmetic shift, which rounds toward negative infinity, e.g. `-5 >> 1` is `-3`
+impl Shr<u32> for BigInt {
+    type Output = BigInt;
+
+    fn shr(self, amount: u32) -> BigInt {
+        let mut binary = self.to_binary();
+        let sign = sign_limb(&binary);
+        let (limbs, bits) = ((amount / 32) as usize, amount % 32);
+        if limbs >= binary.len() {
+            return BigInt::from_binary(vec![sign]);
+        }
+        binary.drain(0..limbs);
+        if bits > 0 {
+            for idx in 0..binary.len() {
+                let carry = binary.get(idx + 1).copied().unwrap_or(sign) << (32 - bits);
+                binary[idx] = (binary[idx] >> bits) | carry;
+            }
+        }
+        BigInt::from_binary(binary)
+    }
+}
+
+/// the limb filled with the sign bit of the two's complement
+fn sign_limb(binary: &[u32]) -> u32 {
+    match binary.last() {
+        Some(limb) if limb >> 31 == 1 => u32::MAX,
+        _ => 0,
+    }
+}
+
+/// extend the two's complement to `len` limbs without changing its value
+fn sign_extend(binary: &mut Vec<u32>, len: usize) {
+    let sign = sign_limb(binary);
+    binary.resize(len, sign);
+}
+
+/// the two's complement negation, `!x + 1`
+fn negate_binary(binary: Vec<u32>) -> Vec<u32> {
+    let mut carry = true;
+    binary
+        .into_iter()
+        .map(|limb| {
+            let (sum, overflow) = (!limb).overflowing_add(carry as u32);
+            carry = overflow;
+            sum
+        })
+        .collect()
+}
+
+/// remove the leading zero limbs
+fn trim(mut limbs: Vec<u32>) -> Vec<u32> {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    limbs
+}
+
+/// compare two magnitudes without leading zero limbs
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+    for idx in 0..a.len().max(b.len()) {
+        let sum = *a.get(idx).unwrap_or(&0) as u64 + *b.get(idx).unwrap_or(&0) as u64 + carry;
+        result.push((sum % BASE) as u32);
+        carry = sum / BASE;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+/// `a - b`, where the magnitude of `a` must not be less than `b`
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+    for (idx, limb) in a.iter().enumerate() {
+        let mut diff = *limb as i64 - *b.get(idx).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += BASE as i64;
+            borrow = 1;
+        }
+        result.push(diff as u32);
+    }
+    trim(result)
+}
+
+/// `limbs * factor + addend` in place
+fn mul_add_small(limbs: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for limb in limbs.iter_mut() {
+        let cur = *limb as u64 * factor as u64 + carry;
+        *limb = (cur % BASE) as u32;
+        carry = cur / BASE;
+    }
+    while carry > 0 {
+        limbs.push((carry % BASE) as u32);
+        carry /= BASE;
+    }
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut result = vec![0_u64; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, y) in b.iter().enumerate() {
+            let cur = result[i + j] + *x as u64 * *y as u64 + carry;
+            result[i + j] = cur % BASE;
+            carry = cur / BASE;
+        }
+        result[i + b.len()] += carry;
+    }
+    trim(result.into_iter().map(|limb| limb as u32).collect())
+}
+
+/// schoolbook long division, `b` must not be zero
+///
+/// every limb of the quotient is found by a binary search in `[0, BASE)`
+fn div_rem_mag(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let mut quotient = vec![0; a.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+    for idx in (0..a.len()).rev() {
+        // remainder = remainder * BASE + a[idx]
+        remainder.insert(0, a[idx]);
+        remainder = trim(remainder);
+
+        let (mut low, mut high) = (0, BASE as u32 - 1);
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            match cmp_mag(&mul_mag(b, &[mid]), &remainder) {
+                Ordering::Greater => high = mid - 1,
+                _ => low = mid,
+            }
+        }
+        if low > 0 {
+            remainder = sub_mag(&remainder, &mul_mag(b, &[low]));
+        }
+        quotient[idx] = low;
+    }
+    (quotient, remainder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!("0", big("0").to_string());
+        assert_eq!("0", big("-0").to_string());
+        assert_eq!("0", big("000").to_string());
+        assert_eq!("42", big("+42").to_string());
+        assert_eq!("-1000000000", big("-1000000000").to_string());
+        assert_eq!(
+            "123456789012345678901234567890",
+            big("123456789012345678901234567890").to_string()
+        );
+        assert!("".parse::<BigInt>().is_err());
+        assert!("-".parse::<BigInt>().is_err());
+        assert!("1.5".parse::<BigInt>().is_err());
+    }
+
+    #[test]
+    fn from_primitive() {
+        assert_eq!(
+            big("-170141183460469231731687303715884105728"),
+            BigInt::from(i128::MIN)
+        );
+        assert_eq!(big("9223372036854775807"), BigInt::from(i64::MAX));
+        assert_eq!(BigInt::zero(), BigInt::from(0_i64));
+    }
+
+    #[test]
+    fn compare() {
+        assert!(big("-5") < big("3"));
+        assert!(big("-5") < big("-3"));
+        assert!(big("1000000000") > big("999999999"));
+        assert!(big("-1000000000") < big("-999999999"));
+    }
+
+    #[test]
+    fn add_and_sub() {
+        assert_eq!(big("1000000000"), big("999999999") + big("1"));
+        assert_eq!(big("-1"), big("999999999") - big("1000000000"));
+        assert_eq!(big("0"), big("-123456789012") + big("123456789012"));
+        assert_eq!(big("-2"), big("-1") + big("-1"));
+        assert_eq!(
+            big("100000000000000000000"),
+            big("99999999999999999999") - big("-1")
+        );
+    }
+
+    #[test]
+    fn mul() {
+        assert_eq!(
+            big("9999999999999999999800000000000000000001"),
+            big("99999999999999999999") * big("99999999999999999999")
+        );
+        assert_eq!(big("-6"), big("-2") * big("3"));
+        assert_eq!(big("0"), big("-2") * big("0"));
+    }
+
+    #[test]
+    fn div_and_rem() {
+        let (q, r) = big("9999999999999999999800000000000000000001")
+            .checked_div_rem(&big("99999999999999999999"))
+            .unwrap();
+        assert_eq!(big("99999999999999999999"), q);
+        assert_eq!(big("0"), r);
+
+        // truncated toward zero, the remainder has the sign of the dividend
+        assert_eq!(
+            Some((big("-2"), big("-1"))),
+            big("-7").checked_div_rem(&big("3"))
+        );
+        assert_eq!(
+            Some((big("-2"), big("1"))),
+            big("7").checked_div_rem(&big("-3"))
+        );
+        assert_eq!(
+            Some(big("12345678901")),
+            big("1524157875323881726870921").checked_div(&big("123456789012345"))
+        );
+        assert_eq!(None, big("1").checked_div(&big("0")));
+    }
+
+    #[test]
+    fn radix() {
+        assert_eq!(Ok(big("255")), BigInt::from_str_radix("ff", 16));
+        assert_eq!(Ok(big("15")), BigInt::from_str_radix("17", 8));
+        assert_eq!(Ok(big("10")), BigInt::from_str_radix("1010", 2));
+        assert_eq!(
+            Ok(big("340282366920938463463374607431768211455")),
+            BigInt::from_str_radix("ffffffffffffffffffffffffffffffff", 16)
+        );
+        assert!(BigInt::from_str_radix("2", 2).is_err());
+        assert!(BigInt::from_str_radix("", 16).is_err());
+        assert!(BigInt::from_str_radix("0", 0).is_err());
+        assert!(BigInt::from_str_radix("0", 1).is_err());
+        assert!(BigInt::from_str_radix("z", 36).is_err());
+        assert!(BigInt::from_str_radix("1é", 16).is_err());
+        // across many chunks of digits
+        assert_eq!(
+            Ok((BigInt::from(1) << 400) - BigInt::from(1)),
+            BigInt::from_str_radix(&"f".repeat(100), 16)
+        );
+        assert_eq!(
+            Ok(BigInt::from(1) << 100),
+            BigInt::from_str_radix(&format!("1{}", "0".repeat(100)), 2)
+        );
+
+        assert_eq!("FF", big("255").to_str_radix(16));
+        assert_eq!("-1010", big("-10").to_str_radix(2));
+        assert_eq!("0", big("0").to_str_radix(8));
+        assert_eq!(
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            big("340282366920938463463374607431768211455").to_str_radix(16)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "radix must be between 2 and 16")]
+    fn to_str_radix_panics_at_radix_1() {
+        big("10").to_str_radix(1);
+    }
+
+    #[test]
+    fn bitwise() {
+        assert_eq!(big("5"), big("-1") & big("5"));
+        assert_eq!(big("-1"), big("-8") | big("7"));
+        assert_eq!(big("-4294967296"), !big("4294967295"));
+        assert_eq!(big("1267650600228229401496703205376"), big("1") << 100);
+        assert_eq!(big("1"), big("1267650600228229401496703205376") >> 100);
+        assert_eq!(big("-1"), big("-1267650600228229401496703205376") >> 200);
+        assert_eq!(big("0"), big("12345") >> 200);
+        assert_eq!(Some(4294967295), big("4294967295").to_u32());
+        assert_eq!(None, big("4294967296").to_u32());
+        assert_eq!(None, big("-1").to_u32());
+    }
+
+    #[test]
+    fn checked_shl() {
+        let max = BigInt::MAX_BITS as u32;
+        assert_eq!(Some(big("1") << 100), big("1").checked_shl(100));
+        assert!(big("1").checked_shl(max).is_some());
+        assert_eq!(None, big("1").checked_shl(max + 1));
+        assert_eq!(None, big("1").checked_shl(4_000_000_000));
+        // the size of the value counts too
+        assert_eq!(None, (big("1") << 1000).checked_shl(max - 100));
+    }
+
+    #[test]
+    fn log2_lower() {
+        assert_eq!(0, big("0").log2_lower());
+        assert_eq!(0, big("-1").log2_lower());
+        assert_eq!(1, big("3").log2_lower());
+        assert_eq!(31, big("4294967295").log2_lower());
+        for amount in [0, 31, 32, 100, 1000] {
+            let value = big("1") << amount;
+            assert!(value.log2_lower() <= amount as u64);
+            assert!(value.log2_lower() + amount as u64 / 30 >= amount as u64);
+        }
+    }
+
+    #[test]
+    fn agree_with_i128() {
+        let values: [i128; 10] = [
+            0,
+            1,
+            -7,
+            999_999_999,
+            -1_000_000_000,
+            123_456_789_012_345,
+            i64::MAX as i128,
+            i64::MIN as i128,
+            -98_765_432_109_876_543_210,
+            1 << 100,
+        ];
+        for a in values {
+            assert_eq!(BigInt::from(!a), !BigInt::from(a));
+            for amount in [0, 1, 31, 32, 33, 64, 100] {
+                assert_eq!(BigInt::from(a >> amount), BigInt::from(a) >> amount);
+                if let Some(shifted) = a.checked_mul(1 << amount) {
+                    assert_eq!(BigInt::from(shifted), BigInt::from(a) << amount);
+                }
+            }
+            for b in values {
+                let (x, y) = (BigInt::from(a), BigInt::from(b));
+                assert_eq!(BigInt::from(a + b), x.clone() + y.clone());
+                assert_eq!(BigInt::from(a - b), x.clone() - y.clone());
+                assert_eq!(a.cmp(&b), x.cmp(&y));
+                if let Some(product) = a.checked_mul(b) {
+                    assert_eq!(BigInt::from(product), x.clone() * y.clone());
+                }
+                if b != 0 {
+                    assert_eq!(
+                        Some((BigInt::from(a / b), BigInt::from(a % b))),
+                        x.checked_div_rem(&y)
+                    );
+                }
+                assert_eq!(BigInt::from(a & b), x.clone() & y.clone());
+                assert_eq!(BigInt::from(a | b), x.clone() | y.clone());
+                assert_eq!(BigInt::from(a ^ b), x.clone() ^ y.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use std::fmt;
+
+use crate::{
+    span::{line_col, Span},
+    syntax_kind::SyntaxKind,
+    traversal::{Arity, ValueType},
+};
+
+/// all the errors that may occur while building an AST from a expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// a character which can not be accepted by the lexer
+    Lexical { span: Span, found: char },
+
+    /// a token which is not allowed at its position
+    UnexpectedToken {
+        span: Span,
+        found: String,
+        expected: Vec<SyntaxKind>,
+    },
+
+    /// the input ends while the parser is still expecting something
+    ///
+    /// span: the empty span right after the last token
+    UnexpectedEof {
+        span: Span,
+        expected: Vec<SyntaxKind>,
+    },
+
+    /// a complete expression was parsed but some tokens are left over
+    ///
+    /// span: the span of the first unconsumed token
+    TrailingInput { span: Span, found: String },
+
+    /// the expression is nested more than `MAX_DEPTH` levels, e.g. by too many parentheses
+    ///
+    /// span: the first token beyond the limit, or the empty span right after the last token
+    TooDeep { span: Span },
+
+    /// nothing but whitespace was received
+    EmptyInput,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Lexical { span, found } => {
+                write!(f, "unexpected character `{}` at {}", found, span)
+            }
+            Error::UnexpectedToken {
+                span,
+                found,
+                expected,
+            } => {
+                write!(f, "unexpected token `{}` at {}", found, span)?;
+                write_expected(f, expected)
+            }
+            Error::UnexpectedEof { span, expected } => {
+                write!(f, "unexpected end of input at {}", span.start)?;
+                write_expected(f, expected)
+            }
+            Error::TrailingInput { span, found } => {
+                write!(f, "unexpected trailing token `{}` at {}", found, span)
+            }
+            Error::TooDeep { span } => write!(f, "expression nested too deeply at {}", span),
+            Error::EmptyInput => write!(f, "an empty string was received"),
+        }
+    }
+}
+
+impl Error {
+    /// the span of the source which the error refers to, if any
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Error::Lexical { span, .. }
+            | Error::UnexpectedToken { span, .. }
+            | Error::UnexpectedEof { span, .. }
+            | Error::TrailingInput { span, .. }
+            | Error::TooDeep { span } => Some(*span),
+            Error::EmptyInput => None,
+        }
+    }
+    /// the 1-based (line, column) of the source which the error refers to, if any
+    ///
+    /// # Example
+    /// ```rust
+    /// let src = "1 +\n2 # 3";
+    /// assert_eq!(Some((2, 3)), build_ast(src).unwrap_err().line_col(src));
+    /// ```
+    pub fn line_col(&self, src: &str) -> Option<(usize, usize)> {
+        self.span().map(|span| line_col(src, span.start))
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// an error which occurs while evaluating a AST
+///
+/// span: the span of the sub-expression that failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalError {
+    pub kind: EvalErrorKind,
+    pub span: Span,
+}
+
+/// all the kinds of errors that may occur during evaluation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalErrorKind {
+    /// the result does not fit in the integer type
+    Overflow,
+    /// the right operand of a division is zero
+    DivisionByZero,
+    /// the minimum value divided by -1, e.g. `i32::MIN / -1`
+    DivisionOverflow,
+    /// the literal can not be represented by the numeric type, e.g. `1.5` in integer arithmetic
+    InvalidLiteral,
+    /// the exponent is not an integer, and the power can not be represented exactly, e.g. `2 ** 0.5`
+    NonIntegerExponent,
+    /// no value is supplied for the variable
+    UnboundVariable,
+    /// no function is registered with the name
+    UnknownFunction,
+    /// the function is called with a wrong number of arguments
+    ArityMismatch { expected: Arity, found: usize },
+    /// the power would have more than `BigInt::MAX_BITS` bits in the exact arithmetic, e.g. `2 ** 4000000000`
+    ExponentTooLarge,
+    /// the amount of a shift is negative or not less than the width of the type, e.g. `1 << 32` for `i32`,
+    /// or the left shift would have more than `BigInt::MAX_BITS` bits
+    ShiftOutOfRange,
+    /// the numeric type does not support the operator, e.g. `1 & 2` in floating-point arithmetic
+    UnsupportedOperation,
+    /// the operand has a wrong type, e.g. `!1` or `(1 < 2) + 1`
+    TypeMismatch {
+        expected: ValueType,
+        found: ValueType,
+    },
+}
+
+impl EvalError {
+    pub fn new(kind: EvalErrorKind, span: Span) -> Self {
+        EvalError { kind, span }
+    }
+    /// the 1-based (line, column) of the sub-expression that failed
+    pub fn line_col(&self, src: &str) -> (usize, usize) {
+        line_col(src, self.span.start)
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            EvalErrorKind::Overflow => write!(f, "arithmetic overflow")?,
+            EvalErrorKind::DivisionByZero => write!(f, "division by zero")?,
+            EvalErrorKind::DivisionOverflow => {
+                write!(f, "overflow in division of the minimum value by -1")?
+            }
+            EvalErrorKind::InvalidLiteral => write!(f, "invalid literal")?,
+            EvalErrorKind::NonIntegerExponent => write!(f, "non-integer exponent")?,
+            EvalErrorKind::UnboundVariable => write!(f, "unbound variable")?,
+            EvalErrorKind::UnknownFunction => write!(f, "unknown function")?,
+            EvalErrorKind::ArityMismatch { expected, found } => write!(
+                f,
+                "expected {} argument(s) but {} were supplied",
+                expected, found
+            )?,
+            EvalErrorKind::ExponentTooLarge => write!(f, "exponent too large")?,
+            EvalErrorKind::ShiftOutOfRange => write!(f, "shift amount out of range")?,
+            EvalErrorKind::UnsupportedOperation => {
+                write!(f, "operation not supported by the numeric type")?
+            }
+            EvalErrorKind::TypeMismatch { expected, found } => {
+                write!(f, "expected a {} but found a {}", expected, found)?
+            }
+        }
+        write!(f, " at {}", self.span)
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// append the expected token kinds to the message, e.g. ", expected one of `(`, `number`"
+fn write_expected(f: &mut fmt::Formatter<'_>, expected: &[SyntaxKind]) -> fmt::Result {
+    match expected {
+        [] => Ok(()),
+        [kind] => write!(f, ", expected `{}`", kind.into_str()),
+        _ => {
+            write!(f, ", expected one of ")?;
+            for (idx, kind) in expected.iter().enumerate() {
+                if idx > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "`{}`", kind.into_str())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax_kind::{CLOSE_PAREN, NUM, OPEN_PAREN};
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            "unexpected character `a` at 2..3",
+            Error::Lexical {
+                span: Span::new(2, 3),
+                found: 'a'
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "unexpected token `)` at 4..5, expected one of `number`, `(`",
+            Error::UnexpectedToken {
+                span: Span::new(4, 5),
+                found: ")".to_string(),
+                expected: vec![NUM, OPEN_PAREN]
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "unexpected end of input at 6, expected `)`",
+            Error::UnexpectedEof {
+                span: Span::new(6, 6),
+                expected: vec![CLOSE_PAREN]
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "expression nested too deeply at 33..34",
+            Error::TooDeep {
+                span: Span::new(33, 34)
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "an empty string was received",
+            Error::EmptyInput.to_string()
+        );
+    }
+
+    #[test]
+    fn eval_error_display() {
+        assert_eq!(
+            "division by zero at 0..3",
+            EvalError::new(EvalErrorKind::DivisionByZero, Span::new(0, 3)).to_string()
+        );
+        assert_eq!(
+            "expected 2 argument(s) but 3 were supplied at 0..12",
+            EvalError::new(
+                EvalErrorKind::ArityMismatch {
+                    expected: Arity::Exact(2),
+                    found: 3
+                },
+                Span::new(0, 12)
+            )
+            .to_string()
+        );
+        assert_eq!(
+            "expected a boolean but found a number at 1..2",
+            EvalError::new(
+                EvalErrorKind::TypeMismatch {
+                    expected: ValueType::Bool,
+                    found: ValueType::Number
+                },
+                Span::new(1, 2)
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn line_col() {
+        let src = "1 +\r\n\t(2 # 3)";
+        let err = Error::Lexical {
+            span: Span::new(9, 10),
+            found: '#',
+        };
+        assert_eq!(Some((2, 5)), err.line_col(src));
+        assert_eq!(None, Error::EmptyInput.line_col(src));
+
+        let err = EvalError::new(EvalErrorKind::DivisionByZero, Span::new(6, 13));
+        assert_eq!((2, 2), err.line_col(src));
+    }
+}
@@ -3,77 +3,142 @@ pub const START: usize = 1;
 pub const OPERATOR: usize = 2;
 pub const ZERO: usize = 3;
 pub const NUM: usize = 4;
+pub const DOT: usize = 5;
+pub const FRACTION: usize = 6;
+pub const EXP: usize = 7;
+pub const EXP_SIGN: usize = 8;
+pub const EXP_NUM: usize = 9;
+pub const IDENT: usize = 10;
+pub const NUM_SEP: usize = 11;
+pub const FRACTION_SEP: usize = 12;
+pub const HEX_PREFIX: usize = 13;
+pub const HEX: usize = 14;
+pub const HEX_SEP: usize = 15;
+pub const OCT_PREFIX: usize = 16;
+pub const OCT: usize = 17;
+pub const OCT_SEP: usize = 18;
+pub const BIN_PREFIX: usize = 19;
+pub const BIN: usize = 20;
+pub const BIN_SEP: usize = 21;
 
-/// a closure function will return
-/// which used to determine whether the giving state is a termination state
+/// whether the giving state is a termination state
 ///
 /// # Example
 /// ```rust
-/// let is_terminator = get_terminator_judgument();
 /// assert!(is_terminator(OPERATOR));
 /// assert!(is_terminator(NUM));
+/// assert!(!is_terminator(DOT));
 /// ```
-pub fn get_terminator_judgement() -> impl Fn(usize) -> bool {
+pub fn is_terminator(state: usize) -> bool {
     /// all the termination state
-    const END_STATE: [usize; 3] = [OPERATOR, ZERO, NUM];
-    |state: usize| END_STATE.contains(&state)
+    const END_STATE: [usize; 9] = [OPERATOR, ZERO, NUM, FRACTION, EXP_NUM, IDENT, HEX, OCT, BIN];
+    END_STATE.contains(&state)
 }
 
-/// returns a closure function for state transition as a helper
+/// the state transferred to from the `state` by the character
 ///
 /// # Example
 /// ```rust
-/// let transition = get_transition();
-///
 /// let mut state = START;
 /// state = transition('+', state);
 /// assert_eq!(OPERATOR, state);
 /// ```
-pub fn get_transition() -> impl Fn(char, usize) -> usize {
+pub fn transition(c: char, state: usize) -> usize {
     /// hardcode state transfer table
     ///
-    /// |              | op  | ws  | 0   | 1-9 |
-    /// |--------------|-----|-----|-----|-----|
-    /// | ERROR        | E   | E   | E   | E   |
-    /// | START        | 2   | 1   | 3   | 4   |
-    /// | OPERATOR     | 2   | 1   | 3   | 4   |
-    /// | ZERO         | 2   | 1   | E   | E   |
-    /// | NUM          | 2   | 1   | 4   | 4   |
+    /// |              | +-  | op  | ws  | 0   | 1   | 2-7 | 8-9 | .   | eE  | bB  | a-f | oO  | xX  | g-z | _   |
+    /// |--------------|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|
+    /// | ERROR        | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   |
+    /// | START        | 2   | 2   | 1   | 3   | 4   | 4   | 4   | E   | 10  | 10  | 10  | 10  | 10  | 10  | 10  |
+    /// | OPERATOR     | 2   | 2   | 1   | 3   | 4   | 4   | 4   | E   | 10  | 10  | 10  | 10  | 10  | 10  | 10  |
+    /// | ZERO         | 2   | 2   | 1   | E   | E   | E   | E   | 5   | 7   | 19  | E   | 16  | 13  | E   | E   |
+    /// | NUM          | 2   | 2   | 1   | 4   | 4   | 4   | 4   | 5   | 7   | E   | E   | E   | E   | E   | 11  |
+    /// | DOT          | E   | E   | E   | 6   | 6   | 6   | 6   | E   | E   | E   | E   | E   | E   | E   | E   |
+    /// | FRACTION     | 2   | 2   | 1   | 6   | 6   | 6   | 6   | E   | 7   | E   | E   | E   | E   | E   | 12  |
+    /// | EXP          | 8   | E   | E   | 9   | 9   | 9   | 9   | E   | E   | E   | E   | E   | E   | E   | E   |
+    /// | EXP_SIGN     | E   | E   | E   | 9   | 9   | 9   | 9   | E   | E   | E   | E   | E   | E   | E   | E   |
+    /// | EXP_NUM      | 2   | 2   | 1   | 9   | 9   | 9   | 9   | E   | E   | E   | E   | E   | E   | E   | E   |
+    /// | IDENT        | 2   | 2   | 1   | 10  | 10  | 10  | 10  | E   | 10  | 10  | 10  | 10  | 10  | 10  | 10  |
+    /// | NUM_SEP      | E   | E   | E   | 4   | 4   | 4   | 4   | E   | E   | E   | E   | E   | E   | E   | E   |
+    /// | FRACTION_SEP | E   | E   | E   | 6   | 6   | 6   | 6   | E   | E   | E   | E   | E   | E   | E   | E   |
+    /// | HEX_PREFIX   | E   | E   | E   | 14  | 14  | 14  | 14  | E   | 14  | 14  | 14  | E   | E   | E   | E   |
+    /// | HEX          | 2   | 2   | 1   | 14  | 14  | 14  | 14  | E   | 14  | 14  | 14  | E   | E   | E   | 15  |
+    /// | HEX_SEP      | E   | E   | E   | 14  | 14  | 14  | 14  | E   | 14  | 14  | 14  | E   | E   | E   | E   |
+    /// | OCT_PREFIX   | E   | E   | E   | 17  | 17  | 17  | E   | E   | E   | E   | E   | E   | E   | E   | E   |
+    /// | OCT          | 2   | 2   | 1   | 17  | 17  | 17  | E   | E   | E   | E   | E   | E   | E   | E   | 18  |
+    /// | OCT_SEP      | E   | E   | E   | 17  | 17  | 17  | E   | E   | E   | E   | E   | E   | E   | E   | E   |
+    /// | BIN_PREFIX   | E   | E   | E   | 20  | 20  | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   |
+    /// | BIN          | 2   | 2   | 1   | 20  | 20  | E   | E   | E   | E   | E   | E   | E   | E   | E   | 21  |
+    /// | BIN_SEP      | E   | E   | E   | 20  | 20  | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   |
     ///
-    const STATE_TABLE: [(usize, usize, usize, usize); 5] = [
-        (0, 0, 0, 0), // ERROR
-        (2, 1, 3, 4), // START
-        (2, 1, 3, 4), // OPERATOR
-        (2, 1, 0, 0), // ZERO
-        (2, 1, 4, 4), // NUM
+    /// `a-f` is the hexadecimal letters except `b` and `e`, `g-z` is the other letters except `o` and `x`
+    #[rustfmt::skip]
+    const STATE_TABLE: [[usize; 15]; 22] = [
+        [ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR], // ERROR
+        [OPERATOR, OPERATOR, START, ZERO, NUM, NUM, NUM, ERROR, IDENT, IDENT, IDENT, IDENT, IDENT, IDENT, IDENT], // START
+        [OPERATOR, OPERATOR, START, ZERO, NUM, NUM, NUM, ERROR, IDENT, IDENT, IDENT, IDENT, IDENT, IDENT, IDENT], // OPERATOR
+        [OPERATOR, OPERATOR, START, ERROR, ERROR, ERROR, ERROR, DOT, EXP, BIN_PREFIX, ERROR, OCT_PREFIX, HEX_PREFIX, ERROR, ERROR], // ZERO
+        [OPERATOR, OPERATOR, START, NUM, NUM, NUM, NUM, DOT, EXP, ERROR, ERROR, ERROR, ERROR, ERROR, NUM_SEP], // NUM
+        [ERROR, ERROR, ERROR, FRACTION, FRACTION, FRACTION, FRACTION, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR], // DOT
+        [OPERATOR, OPERATOR, START, FRACTION, FRACTION, FRACTION, FRACTION, ERROR, EXP, ERROR, ERROR, ERROR, ERROR, ERROR, FRACTION_SEP], // FRACTION
+        [EXP_SIGN, ERROR, ERROR, EXP_NUM, EXP_NUM, EXP_NUM, EXP_NUM, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR], // EXP
+        [ERROR, ERROR, ERROR, EXP_NUM, EXP_NUM, EXP_NUM, EXP_NUM, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR], // EXP_SIGN
+        [OPERATOR, OPERATOR, START, EXP_NUM, EXP_NUM, EXP_NUM, EXP_NUM, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR], // EXP_NUM
+        [OPERATOR, OPERATOR, START, IDENT, IDENT, IDENT, IDENT, ERROR, IDENT, IDENT, IDENT, IDENT, IDENT, IDENT, IDENT], // IDENT
+        [ERROR, ERROR, ERROR, NUM, NUM, NUM, NUM, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR], // NUM_SEP
+        [ERROR, ERROR, ERROR, FRACTION, FRACTION, FRACTION, FRACTION, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR], // FRACTION_SEP
+        [ERROR, ERROR, ERROR, HEX, HEX, HEX, HEX, ERROR, HEX, HEX, HEX, ERROR, ERROR, ERROR, ERROR], // HEX_PREFIX
+        [OPERATOR, OPERATOR, START, HEX, HEX, HEX, HEX, ERROR, HEX, HEX, HEX, ERROR, ERROR, ERROR, HEX_SEP], // HEX
+        [ERROR, ERROR, ERROR, HEX, HEX, HEX, HEX, ERROR, HEX, HEX, HEX, ERROR, ERROR, ERROR, ERROR], // HEX_SEP
+        [ERROR, ERROR, ERROR, OCT, OCT, OCT, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR], // OCT_PREFIX
+        [OPERATOR, OPERATOR, START, OCT, OCT, OCT, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, OCT_SEP], // OCT
+        [ERROR, ERROR, ERROR, OCT, OCT, OCT, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR], // OCT_SEP
+        [ERROR, ERROR, ERROR, BIN, BIN, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR], // BIN_PREFIX
+        [OPERATOR, OPERATOR, START, BIN, BIN, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, BIN_SEP], // BIN
+        [ERROR, ERROR, ERROR, BIN, BIN, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR, ERROR], // BIN_SEP
     ];
-    let is_op = |c: char| match c {
-        '-' | '+' | '*' | '/' | '(' | ')' => true,
-        _ => false,
-    };
-    let is_whitespace = |c: char| match c {
-        ' ' => true,
-        _ => false,
-    };
-    let is_zero = |c: char| match c {
-        '0' => true,
-        _ => false,
+    let input = match c {
+        '-' | '+' => 0,
+        c if is_op(c) => 1,
+        // all the Unicode whitespace, e.g. `\t`, `\n`, `\r` and `\u{3000}`
+        c if c.is_whitespace() => 2,
+        '0' => 3,
+        '1' => 4,
+        '2'..='7' => 5,
+        '8' | '9' => 6,
+        '.' => 7,
+        // the letters with a meaning in a number are letters of a name too,
+        // every letter column leads to `IDENT` where a name may go
+        'e' | 'E' => 8,
+        'b' | 'B' => 9,
+        'a' | 'c' | 'd' | 'f' | 'A' | 'C' | 'D' | 'F' => 10,
+        'o' | 'O' => 11,
+        'x' | 'X' => 12,
+        c if c.is_ascii_alphabetic() => 13,
+        '_' => 14,
+        _ => return ERROR,
     };
-    let is_one_to_nine = |c: char| match c {
-        '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => true,
-        _ => false,
-    };
-    move |c: char, state: usize| {
-        if is_op(c) {
-            STATE_TABLE[state].0
-        } else if is_whitespace(c) {
-            STATE_TABLE[state].1
-        } else if is_zero(c) {
-            STATE_TABLE[state].2
-        } else if is_one_to_nine(c) {
-            STATE_TABLE[state].3
-        } else {
-            ERROR
-        }
-    }
+    STATE_TABLE[state][input]
+}
+
+/// the characters of the operators except the signs
+fn is_op(c: char) -> bool {
+    matches!(
+        c,
+        '*' | '/'
+            | '%'
+            | '('
+            | ')'
+            | ','
+            | '='
+            | '!'
+            | '<'
+            | '>'
+            | '&'
+            | '|'
+            | '?'
+            | ':'
+            | '^'
+            | '~'
+    )
 }
@@ -1,83 +1,83 @@
 mod dfa;
 mod tokenizer;
 
-use tokenizer::Tokenizer;
+use crate::error::Error;
 
-pub use tokenizer::{Token, TokenStream};
+pub use tokenizer::{to_owned_token, OwnedToken, Token, TokenStream, Tokenizer};
 
 /// Lexer
 /// convert a character stream to a token stream
 /// return a `Result` to indicate whether it is successful or not
 /// - Ok(TokenStream)
-/// - Err(ErrorMessage)
-pub fn lex(code: &str) -> Result<TokenStream, String> {
-    let mut tokenizer = Tokenizer::new(code.to_string());
-    match tokenizer.run() {
-        Ok(_) => Ok(tokenizer.token_stream()),
-        Err(err) => Err(err),
-    }
+/// - Err(Error)
+pub fn lex(code: &str) -> Result<TokenStream<'_>, Error> {
+    Tokenizer::new(code).collect()
 }
 
 #[cfg(test)]
 mod tests {
     use crate::lex;
+    use crate::span::Span;
     use crate::syntax_kind::*;
 
     #[test]
     fn smoke() {
         assert_eq!(
             vec![
-                (OPEN_PAREN, "(".to_string()),
-                (NUM, "1".to_string()),
-                (PLUS, "+".to_string()),
-                (NUM, "2".to_string()),
-                (CLOSE_PAREN, ")".to_string()),
-                (PLUS, "+".to_string()),
-                (NUM, "3".to_string()),
+                (OPEN_PAREN, "(", Span::new(0, 1)),
+                (NUM, "1", Span::new(1, 2)),
+                (PLUS, "+", Span::new(3, 4)),
+                (NUM, "2", Span::new(5, 6)),
+                (CLOSE_PAREN, ")", Span::new(6, 7)),
+                (PLUS, "+", Span::new(8, 9)),
+                (NUM, "3", Span::new(10, 11)),
             ],
             lex("(1 + 2) + 3").unwrap()
         );
         assert_eq!(
             vec![
-                (NUM, "1".to_string()),
-                (PLUS, "+".to_string()),
-                (OPEN_PAREN, "(".to_string()),
-                (NUM, "2".to_string()),
-                (PLUS, "+".to_string()),
-                (NUM, "3".to_string()),
-                (CLOSE_PAREN, ")".to_string()),
+                (NUM, "1", Span::new(0, 1)),
+                (PLUS, "+", Span::new(2, 3)),
+                (OPEN_PAREN, "(", Span::new(4, 5)),
+                (NUM, "2", Span::new(5, 6)),
+                (PLUS, "+", Span::new(7, 8)),
+                (NUM, "3", Span::new(9, 10)),
+                (CLOSE_PAREN, ")", Span::new(10, 11)),
             ],
             lex("1 + (2 + 3)").unwrap()
         );
         assert_eq!(
             vec![
-                (OPEN_PAREN, "(".to_string()),
-                (NUM, "1".to_string()),
-                (CLOSE_PAREN, ")".to_string()),
-                (PLUS, "+".to_string()),
-                (OPEN_PAREN, "(".to_string()),
-                (NUM, "2".to_string()),
-                (CLOSE_PAREN, ")".to_string()),
-                (PLUS, "+".to_string()),
-                (OPEN_PAREN, "(".to_string()),
-                (NUM, "3".to_string()),
-                (CLOSE_PAREN, ")".to_string()),
+                (OPEN_PAREN, "(", Span::new(0, 1)),
+                (NUM, "1", Span::new(1, 2)),
+                (CLOSE_PAREN, ")", Span::new(2, 3)),
+                (PLUS, "+", Span::new(4, 5)),
+                (OPEN_PAREN, "(", Span::new(6, 7)),
+                (NUM, "2", Span::new(7, 8)),
+                (CLOSE_PAREN, ")", Span::new(8, 9)),
+                (PLUS, "+", Span::new(10, 11)),
+                (OPEN_PAREN, "(", Span::new(12, 13)),
+                (NUM, "3", Span::new(13, 14)),
+                (CLOSE_PAREN, ")", Span::new(14, 15)),
             ],
             lex("(1) + (2) + (3)").unwrap()
         );
         assert_eq!(
             vec![
-                (OPEN_PAREN, "(".to_string()),
-                (NUM, "-1".to_string()),
-                (CLOSE_PAREN, ")".to_string()),
-                (PLUS, "+".to_string()),
-                (OPEN_PAREN, "(".to_string()),
-                (NUM, "-2".to_string()),
-                (CLOSE_PAREN, ")".to_string()),
-                (PLUS, "+".to_string()),
-                (OPEN_PAREN, "(".to_string()),
-                (NUM, "-3".to_string()),
-                (CLOSE_PAREN, ")".to_string()),
+                (OPEN_PAREN, "(", Span::new(0, 1)),
+                (MINUS, "-", Span::new(1, 2)),
+                (NUM, "1", Span::new(2, 3)),
+                (CLOSE_PAREN, ")", Span::new(3, 4)),
+                (PLUS, "+", Span::new(5, 6)),
+                (OPEN_PAREN, "(", Span::new(7, 8)),
+                (MINUS, "-", Span::new(8, 9)),
+                (NUM, "2", Span::new(9, 10)),
+                (CLOSE_PAREN, ")", Span::new(10, 11)),
+                (PLUS, "+", Span::new(12, 13)),
+                (OPEN_PAREN, "(", Span::new(14, 15)),
+                (MINUS, "-", Span::new(15, 16)),
+                (NUM, "3", Span::new(16, 17)),
+                (CLOSE_PAREN, ")", Span::new(17, 18)),
             ],
             lex("(-1) + (-2) + (-3)").unwrap()
         );
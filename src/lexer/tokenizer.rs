@@ -1,220 +1,490 @@
+use std::{collections::VecDeque, str::CharIndices};
+
 use crate::{
-    lexer::dfa::{get_terminator_judgement, get_transition, ERROR, OPERATOR, START},
-    syntax_kind::{SyntaxKind, NUM},
-    token,
+    error::Error,
+    lexer::dfa::{self, is_terminator, transition, ERROR, OPERATOR, START},
+    span::Span,
+    syntax_kind::{SyntaxKind, IDENT, NUM},
 };
 
 /// type definition of the token
 ///
 /// kind: token.0
-/// text: token.1
-pub type Token = (SyntaxKind, String);
-pub type TokenStream = Vec<Token>;
+/// text: token.1, borrowed from the source
+/// span: token.2
+pub type Token<'src> = (SyntaxKind, &'src str, Span);
+pub type TokenStream<'src> = Vec<Token<'src>>;
+
+/// the owned form of the token, which outlives the source
+pub type OwnedToken = (SyntaxKind, String, Span);
+
+/// copy the text of a borrowed token to get the owned form
+///
+/// # Example
+/// ```rust
+/// let tokens: Result<Vec<OwnedToken>, Error> = Tokenizer::new("1 + 2").map(|t| t.map(to_owned_token)).collect();
+/// ```
+pub fn to_owned_token((kind, text, span): Token) -> OwnedToken {
+    (kind, text.to_string(), span)
+}
+
+/// the length of the longest operator, e.g. "**"
+const MAX_OPERATOR_LEN: usize = 2;
 
 /// Deterministic Finite Automaton
 ///
 /// DFA = ( StateSet, InputSet, transition_fn, start, TerminatorSet )
 ///
-/// StateSet = { START, OPERATOR, ZERO, NUM }
-/// InputSet = { operator, whitespace, 0, 1-9 }
+/// StateSet = { START, OPERATOR, ZERO, NUM, DOT, FRACTION, EXP, EXP_SIGN, EXP_NUM, IDENT,
+///              NUM_SEP, FRACTION_SEP, HEX_PREFIX, HEX, HEX_SEP, OCT_PREFIX, OCT, OCT_SEP,
+///              BIN_PREFIX, BIN, BIN_SEP }
+/// InputSet = { sign, operator, whitespace, 0, 1, 2-7, 8-9, dot, eE, bB, a-f, oO, xX, g-z, _ }
 /// start = START
-/// TerminatorSet = { OPERATOR, ZERO, NUM }
+/// TerminatorSet = { OPERATOR, ZERO, NUM, FRACTION, EXP_NUM, IDENT, HEX, OCT, BIN }
 ///
 /// transition_table
-/// |              | op  | ws  | 0   | 1-9 |
-/// |--------------|-----|-----|-----|-----|
-/// | ERROR        | E   | E   | E   | E   |
-/// | START        | 2   | 1   | 3   | 4   |
-/// | OPERATOR     | 2   | 1   | 3   | 4   |
-/// | ZERO         | 2   | 1   | E   | E   |
-/// | NUM          | 2   | 1   | 4   | 4   |
+/// |              | +-  | op  | ws  | 0   | 1   | 2-7 | 8-9 | .   | eE  | bB  | a-f | oO  | xX  | g-z | _   |
+/// |--------------|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|-----|
+/// | ERROR        | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   |
+/// | START        | 2   | 2   | 1   | 3   | 4   | 4   | 4   | E   | 10  | 10  | 10  | 10  | 10  | 10  | 10  |
+/// | OPERATOR     | 2   | 2   | 1   | 3   | 4   | 4   | 4   | E   | 10  | 10  | 10  | 10  | 10  | 10  | 10  |
+/// | ZERO         | 2   | 2   | 1   | E   | E   | E   | E   | 5   | 7   | 19  | E   | 16  | 13  | E   | E   |
+/// | NUM          | 2   | 2   | 1   | 4   | 4   | 4   | 4   | 5   | 7   | E   | E   | E   | E   | E   | 11  |
+/// | DOT          | E   | E   | E   | 6   | 6   | 6   | 6   | E   | E   | E   | E   | E   | E   | E   | E   |
+/// | FRACTION     | 2   | 2   | 1   | 6   | 6   | 6   | 6   | E   | 7   | E   | E   | E   | E   | E   | 12  |
+/// | EXP          | 8   | E   | E   | 9   | 9   | 9   | 9   | E   | E   | E   | E   | E   | E   | E   | E   |
+/// | EXP_SIGN     | E   | E   | E   | 9   | 9   | 9   | 9   | E   | E   | E   | E   | E   | E   | E   | E   |
+/// | EXP_NUM      | 2   | 2   | 1   | 9   | 9   | 9   | 9   | E   | E   | E   | E   | E   | E   | E   | E   |
+/// | IDENT        | 2   | 2   | 1   | 10  | 10  | 10  | 10  | E   | 10  | 10  | 10  | 10  | 10  | 10  | 10  |
+/// | NUM_SEP      | E   | E   | E   | 4   | 4   | 4   | 4   | E   | E   | E   | E   | E   | E   | E   | E   |
+/// | FRACTION_SEP | E   | E   | E   | 6   | 6   | 6   | 6   | E   | E   | E   | E   | E   | E   | E   | E   |
+/// | HEX_PREFIX   | E   | E   | E   | 14  | 14  | 14  | 14  | E   | 14  | 14  | 14  | E   | E   | E   | E   |
+/// | HEX          | 2   | 2   | 1   | 14  | 14  | 14  | 14  | E   | 14  | 14  | 14  | E   | E   | E   | 15  |
+/// | HEX_SEP      | E   | E   | E   | 14  | 14  | 14  | 14  | E   | 14  | 14  | 14  | E   | E   | E   | E   |
+/// | OCT_PREFIX   | E   | E   | E   | 17  | 17  | 17  | E   | E   | E   | E   | E   | E   | E   | E   | E   |
+/// | OCT          | 2   | 2   | 1   | 17  | 17  | 17  | E   | E   | E   | E   | E   | E   | E   | E   | 18  |
+/// | OCT_SEP      | E   | E   | E   | 17  | 17  | 17  | E   | E   | E   | E   | E   | E   | E   | E   | E   |
+/// | BIN_PREFIX   | E   | E   | E   | 20  | 20  | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   |
+/// | BIN          | 2   | 2   | 1   | 20  | 20  | E   | E   | E   | E   | E   | E   | E   | E   | E   | 21  |
+/// | BIN_SEP      | E   | E   | E   | 20  | 20  | E   | E   | E   | E   | E   | E   | E   | E   | E   | E   |
+///
+/// the tokens are yielded lazily in a single pass over the characters,
+/// and the iteration stops after the first error
 ///
-pub struct Tokenizer {
-    code: String,
-    token_stream: TokenStream,
+/// # Example
+/// ```rust
+/// let mut tokens = Tokenizer::new("1 + #");
+/// assert_eq!(Some(Ok((NUM, "1", Span::new(0, 1)))), tokens.next());
+/// assert_eq!(Some(Ok((PLUS, "+", Span::new(2, 3)))), tokens.next());
+/// assert!(matches!(tokens.next(), Some(Err(Error::Lexical { .. }))));
+/// assert_eq!(None, tokens.next());
+/// ```
+pub struct Tokenizer<'a> {
+    code: &'a str,
+    chars: CharIndices<'a>,
+    state: usize,
+    /// the start of the currently matched characters, which are always `code[start..pos]`,
+    /// since a whitespace ends the token
+    start: Option<usize>,
+    /// the last character and its position
+    last: (char, usize),
+    /// the tokens which are split but not yielded yet, e.g. "**-" => [ **, - ]
+    pending: VecDeque<Token<'a>>,
+    /// whether any token is yielded, to report an empty input
+    yielded: bool,
+    finished: bool,
 }
 
-impl Tokenizer {
-    pub fn new(code: String) -> Self {
+impl<'a> Tokenizer<'a> {
+    pub fn new(code: &'a str) -> Self {
         Tokenizer {
             code,
-            token_stream: Vec::new(),
+            chars: code.char_indices(),
+            state: START,
+            start: None,
+            last: (' ', 0),
+            pending: VecDeque::new(),
+            yielded: false,
+            finished: false,
         }
     }
-    pub fn token_stream(&self) -> TokenStream {
-        self.token_stream.to_owned()
-    }
-    pub fn run(&mut self) -> Result<(), String> {
-        if self.code.len() == 0 {
-            return Err("an empty string was received".to_string());
-        }
+    /// feed one character to the DFA,
+    /// the cached characters are saved as token(s) while the token is ended by the character
+    fn step(&mut self, pos: usize, c: char) -> Result<(), Error> {
+        let prev_state = self.state;
+        // 1. judge which state to transfer to according to the current character
+        self.state = transition(c, prev_state);
 
-        // state transition function
-        let transition = get_transition();
-        // termination state judgument helper
-        let is_terminator = get_terminator_judgement();
-
-        let mut idx = 0;
-        let mut state = START;
-        let mut prev_state = ERROR;
-
-        // cache currently matched characters
-        let mut text_cache = String::new();
-        while let Some(c) = self.code.chars().nth(idx) {
-            // 1. judge which state to transfer to according to the current character
-            state = transition(c, state);
-
-            // 2.1 panic at ERROR state
-            if state == ERROR {
-                return Err(format!(
-                    "unexpected token at the {} of the input, current cache: {}",
-                    idx, text_cache
-                ));
-            }
-            // 2.2 save the contents of the cache as a token and make it empty
-            //     while:
-            //          (1). a transition between two termination states occurs
-            //          (2). an operator was matched
-            else if is_terminator(prev_state) && (state != prev_state || prev_state == OPERATOR) {
-                self.push_token(&text_cache);
-                text_cache.clear();
+        // 2.1 panic at ERROR state,
+        //     an unfinished number ended by a whitespace or an operator, e.g. "0x + 1",
+        //     is blamed rather than the character which ends it
+        if self.state == ERROR {
+            if self.start.is_some() && matches!(transition(c, START), START | OPERATOR) {
+                return Err(self.incomplete_number());
             }
+            return Err(Error::Lexical {
+                span: Span::new(pos, pos + c.len_utf8()),
+                found: c,
+            });
+        }
+        // 2.2 save the cached characters as token(s) and make the cache empty
+        //     while a termination state is left by:
+        //          (1). a whitespace, which ends the current token
+        //          (2). an operator after a number or a identifier, or the opposite
+        //     the transitions inside a number, e.g. NUM -> DOT -> FRACTION, keep it in the cache,
+        //     and so does a run of operators, e.g. "**", which is split by `push_token`
+        if is_terminator(prev_state)
+            && (self.state == START || (self.state == OPERATOR) != (prev_state == OPERATOR))
+        {
+            self.flush(pos, prev_state)?;
+        }
 
-            // 3. save the current character to the cache, except for whitespace
-            if c != ' ' {
-                text_cache.push(c);
+        // 3. save the current character to the cache, except for whitespace
+        if !c.is_whitespace() && self.start.is_none() {
+            self.start = Some(pos);
+        }
+        self.last = (c, pos);
+        Ok(())
+    }
+    /// the input is exhausted, the last one token is saved if any
+    fn finish(&mut self) -> Result<(), Error> {
+        if self.start.is_some() {
+            // the input must not end in the middle of a number, e.g. "1." or "1e"
+            if !is_terminator(self.state) {
+                return Err(self.incomplete_number());
             }
-
-            // 4. update the index and previous state
-            //    back to the step 1
-            idx += 1;
-            prev_state = state;
+            self.flush(self.code.len(), self.state)?;
+        }
+        match self.yielded || !self.pending.is_empty() {
+            true => Ok(()),
+            false => Err(Error::EmptyInput),
+        }
+    }
+    /// the number which is not finished, e.g. "1." or "0x", is reported at the character where it stopped
+    fn incomplete_number(&self) -> Error {
+        let (c, pos) = self.last;
+        Error::Lexical {
+            span: Span::new(pos, pos + c.len_utf8()),
+            found: c,
+        }
+    }
+    /// save the cached characters, which end at `end`, as token(s)
+    fn flush(&mut self, end: usize, state: usize) -> Result<(), Error> {
+        if let Some(start) = self.start.take() {
+            self.push_token(&self.code[start..end], Span::new(start, end), state)?;
+        }
+        Ok(())
+    }
+    /// push the cached text which is accepted in the `state`
+    ///
+    /// the sign is always a operator token, e.g. "-1" => [ -, 1 ],
+    /// the parser decides whether it is unary or binary
+    fn push_token(&mut self, text: &'a str, span: Span, state: usize) -> Result<(), Error> {
+        match state {
+            OPERATOR => self.push_operators(text, span)?,
+            dfa::IDENT => self.push_token_text(IDENT, text, span),
+            _ => self.push_token_text(NUM, text, span),
         }
-        // 5. the last one token
-        self.push_token(&text_cache);
-
         Ok(())
     }
-    fn push_token(&mut self, text: &str) {
-        let token = match SyntaxKind::from_operator(text) {
-            // is a operator token
-            Some(kind) => (kind, text.to_string()),
-            // is a number token, and it is possible to merge
-            // e.g
-            //         source: "1 + -1"
-            //   token_stream: [ 1, +, - ]  <- 1
-            //
-            //   Awesome: [ 1, +, -1 ]
-            //       Bad: [ 1, +, -, 1 ]
-            None => (NUM, self.try_merge(text.to_string())),
-        };
-        self.token_stream.push(token);
-    }
-    fn try_merge(&mut self, mut text: String) -> String {
-        let len = self.token_stream.len();
-
-        // e.g
-        //   [ 1, +, - ] <- 1
-        //        |  |      |
-        //        k1 k2   text
-        if len >= 2 {
-            let (k1, _) = self.token_stream[len - 2];
-            let (k2, _) = self.token_stream[len - 1];
-            match k1 {
-                token!["+"] | token!["-"] | token!["*"] | token!["/"] | token!["("] => match k2 {
-                    // "1 + - 1" => [ 1, +, -1 ]
-                    token!["-"] => {
-                        self.token_stream.pop();
-                        text.insert_str(0, "-")
-                    }
-                    // "1 + + 1" => [ 1, +, 1]
-                    token!["+"] => {
-                        self.token_stream.pop();
-                    }
-                    _ => {}
-                },
-                _ => {}
+    /// split a run of operators by the longest match, e.g. "**-" => [ **, - ]
+    fn push_operators(&mut self, text: &'a str, span: Span) -> Result<(), Error> {
+        let mut start = 0;
+        while start < text.len() {
+            let rest = &text[start..];
+            let len = (1..=rest.len().min(MAX_OPERATOR_LEN))
+                .rev()
+                .find(|len| SyntaxKind::from_operator(&rest[..*len]).is_some());
+            let pos = span.start + start;
+            match len {
+                Some(len) => {
+                    let kind = SyntaxKind::from_operator(&rest[..len]).unwrap();
+                    self.push_token_text(kind, &rest[..len], Span::new(pos, pos + len));
+                    start += len;
+                }
+                None => {
+                    let c = rest.chars().next().unwrap();
+                    return Err(Error::Lexical {
+                        span: Span::new(pos, pos + c.len_utf8()),
+                        found: c,
+                    });
+                }
             }
         }
-        // e.g
-        //   [ - ] <- 1
-        //     |      |
-        //     k1    text
-        else if len == 1 {
-            let (k1, _) = self.token_stream[len - 1];
-            if k1 == token!["+"] || k1 == token!["-"] {
-                self.token_stream.pop();
-                match k1 {
-                    // "- 1" => [ -1 ]
-                    token!["-"] => text.insert_str(0, "-"),
-                    // "+ 1" => [ 1 ]
-                    _ => {}
+        Ok(())
+    }
+    fn push_token_text(&mut self, kind: SyntaxKind, text: &'a str, span: Span) {
+        self.pending.push_back((kind, text, span));
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(token) = self.pending.pop_front() {
+                self.yielded = true;
+                return Some(Ok(token));
+            }
+            if self.finished {
+                return None;
+            }
+            let result = match self.chars.next() {
+                Some((pos, c)) => self.step(pos, c),
+                None => {
+                    self.finished = true;
+                    self.finish()
                 }
+            };
+            if let Err(err) = result {
+                // nothing is yielded after an error
+                self.finished = true;
+                self.pending.clear();
+                return Some(Err(err));
             }
         }
-        text
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::lexer::tokenizer::Tokenizer;
+    use crate::error::Error;
+    use crate::lexer::tokenizer::{to_owned_token, Tokenizer};
     use crate::lexer::TokenStream;
-    use crate::syntax_kind::{NUM, PLUS};
-
-    fn lex(code: &str) -> Result<TokenStream, ()> {
-        let mut tokenizer = Tokenizer::new(code.to_string());
-        if tokenizer.run().is_ok() {
-            Ok(tokenizer.token_stream())
-        } else {
-            Err(())
-        }
+    use crate::span::Span;
+    use crate::syntax_kind::{
+        AMP, AMP_AMP, BANG, BANG_EQ, CARET, COLON, DOUBLE_SLASH, DOUBLE_STAR, EQ_EQ, GT, GT_EQ,
+        GT_GT, IDENT, LT, LT_LT, MINUS, NUM, PERCENT, PIPE, PIPE_PIPE, PLUS, QUESTION, STAR, TILDE,
+    };
+
+    fn lex(code: &str) -> Result<TokenStream<'_>, Error> {
+        Tokenizer::new(code).collect()
     }
 
     #[test]
     fn basic_test() {
-        assert_eq!(vec![(NUM, "123".to_string())], lex("+123").unwrap());
-        assert_eq!(vec![(NUM, "-123".to_string())], lex("-123").unwrap());
-        assert_eq!(vec![(NUM, "123".to_string())], lex("123").unwrap());
+        assert_eq!(vec![(NUM, "123", Span::new(0, 3))], lex("123").unwrap());
+        assert_eq!(
+            vec![
+                (NUM, "123", Span::new(0, 3)),
+                (PLUS, "+", Span::new(3, 4)),
+                (NUM, "456", Span::new(4, 7))
+            ],
+            lex("123+456").unwrap()
+        );
+    }
 
+    #[test]
+    fn sign_is_a_operator() {
+        assert_eq!(
+            vec![(MINUS, "-", Span::new(0, 1)), (NUM, "123", Span::new(1, 4))],
+            lex("-123").unwrap()
+        );
+        assert_eq!(
+            vec![(PLUS, "+", Span::new(0, 1)), (NUM, "0", Span::new(1, 2))],
+            lex("+0").unwrap()
+        );
         assert_eq!(
             vec![
-                (NUM, "123".to_string()),
-                (PLUS, "+".to_string()),
-                (NUM, "-456".to_string())
+                (NUM, "123", Span::new(0, 3)),
+                (PLUS, "+", Span::new(3, 4)),
+                (MINUS, "-", Span::new(4, 5)),
+                (NUM, "456", Span::new(5, 8))
             ],
             lex("123+-456").unwrap()
         );
         assert_eq!(
             vec![
-                (NUM, "123".to_string()),
-                (PLUS, "+".to_string()),
-                (NUM, "-456".to_string())
+                (NUM, "1", Span::new(0, 1)),
+                (PLUS, "+", Span::new(2, 3)),
+                (MINUS, "-", Span::new(4, 5)),
+                (NUM, "2", Span::new(6, 7))
+            ],
+            lex("1 + - 2").unwrap()
+        );
+    }
+
+    #[test]
+    fn split_operators_by_the_longest_match() {
+        assert_eq!(
+            vec![
+                (NUM, "2", Span::new(0, 1)),
+                (DOUBLE_STAR, "**", Span::new(1, 3)),
+                (MINUS, "-", Span::new(3, 4)),
+                (NUM, "1", Span::new(4, 5))
             ],
-            lex("+123+-456").unwrap()
+            lex("2**-1").unwrap()
         );
         assert_eq!(
             vec![
-                (NUM, "123".to_string()),
-                (PLUS, "+".to_string()),
-                (NUM, "456".to_string())
+                (DOUBLE_STAR, "**", Span::new(0, 2)),
+                (STAR, "*", Span::new(2, 3)),
+                (STAR, "*", Span::new(4, 5))
+            ],
+            lex("*** *").unwrap()
+        );
+        assert_eq!(
+            vec![
+                (NUM, "7", Span::new(0, 1)),
+                (DOUBLE_SLASH, "//", Span::new(1, 3)),
+                (NUM, "2", Span::new(3, 4)),
+                (PERCENT, "%", Span::new(4, 5)),
+                (MINUS, "-", Span::new(5, 6)),
+                (NUM, "3", Span::new(6, 7))
+            ],
+            lex("7//2%-3").unwrap()
+        );
+        assert_eq!(
+            vec![
+                (IDENT, "a", Span::new(0, 1)),
+                (GT_EQ, ">=", Span::new(1, 3)),
+                (MINUS, "-", Span::new(3, 4)),
+                (NUM, "1", Span::new(4, 5)),
+                (AMP_AMP, "&&", Span::new(5, 7)),
+                (BANG, "!", Span::new(7, 8)),
+                (IDENT, "b", Span::new(8, 9)),
+                (PIPE_PIPE, "||", Span::new(9, 11)),
+                (NUM, "1", Span::new(11, 12)),
+                (LT, "<", Span::new(12, 13)),
+                (BANG_EQ, "!=", Span::new(13, 15)),
+                (EQ_EQ, "==", Span::new(15, 17)),
+                (BANG, "!", Span::new(17, 18)),
+                (BANG, "!", Span::new(18, 19))
+            ],
+            lex("a>=-1&&!b||1<!===!!").unwrap()
+        );
+    }
+
+    #[test]
+    fn allow_conditional() {
+        assert_eq!(
+            vec![
+                (IDENT, "a", Span::new(0, 1)),
+                (QUESTION, "?", Span::new(1, 2)),
+                (MINUS, "-", Span::new(2, 3)),
+                (NUM, "1", Span::new(3, 4)),
+                (COLON, ":", Span::new(4, 5)),
+                (BANG, "!", Span::new(5, 6)),
+                (IDENT, "b", Span::new(6, 7))
+            ],
+            lex("a?-1:!b").unwrap()
+        );
+    }
+
+    #[test]
+    fn allow_bitwise_operator() {
+        assert_eq!(
+            vec![
+                (NUM, "1", Span::new(0, 1)),
+                (LT_LT, "<<", Span::new(1, 3)),
+                (NUM, "4", Span::new(3, 4)),
+                (PIPE, "|", Span::new(4, 5)),
+                (TILDE, "~", Span::new(5, 6)),
+                (IDENT, "m", Span::new(6, 7)),
+                (AMP_AMP, "&&", Span::new(7, 9)),
+                (AMP, "&", Span::new(9, 10)),
+                (CARET, "^", Span::new(10, 11)),
+                (GT_GT, ">>", Span::new(11, 13)),
+                (GT, ">", Span::new(13, 14))
             ],
-            lex("+123+456").unwrap()
+            lex("1<<4|~m&&&^>>>").unwrap()
         );
     }
 
     #[test]
-    fn allow_positive_zero_and_negative_zero() {
-        assert_eq!(vec![(NUM, "0".to_string())], lex("0").unwrap());
-        assert_eq!(vec![(NUM, "-0".to_string())], lex("-0").unwrap());
-        assert_eq!(vec![(NUM, "0".to_string())], lex("0").unwrap());
+    fn panic_at_incomplete_operator() {
+        assert_eq!(
+            Err(Error::Lexical {
+                span: Span::new(2, 3),
+                found: '='
+            }),
+            lex("a = 1")
+        );
+    }
+
+    #[test]
+    fn allow_zero() {
+        assert_eq!(vec![(NUM, "0", Span::new(0, 1))], lex("0").unwrap());
     }
 
     #[test]
     fn allow_suffix_zero() {
-        assert_eq!(vec![(NUM, "100".to_string())], lex("+100").unwrap());
-        assert_eq!(vec![(NUM, "-100".to_string())], lex("-100").unwrap());
-        assert_eq!(vec![(NUM, "100".to_string())], lex("100").unwrap());
+        assert_eq!(vec![(NUM, "100", Span::new(0, 3))], lex("100").unwrap());
+    }
+
+    #[test]
+    fn allow_surrounding_whitespace() {
+        assert_eq!(vec![(NUM, "1", Span::new(1, 2))], lex(" 1 ").unwrap());
+    }
+
+    #[test]
+    fn allow_float() {
+        assert_eq!(vec![(NUM, "1.5", Span::new(0, 3))], lex("1.5").unwrap());
+        assert_eq!(vec![(NUM, "0.25", Span::new(0, 4))], lex("0.25").unwrap());
+        assert_eq!(vec![(NUM, "100.0", Span::new(0, 5))], lex("100.0").unwrap());
+        assert_eq!(
+            vec![
+                (NUM, "1.5", Span::new(0, 3)),
+                (STAR, "*", Span::new(3, 4)),
+                (NUM, "2", Span::new(4, 5))
+            ],
+            lex("1.5*2").unwrap()
+        );
+    }
+
+    #[test]
+    fn allow_exponent() {
+        assert_eq!(vec![(NUM, "1e-3", Span::new(0, 4))], lex("1e-3").unwrap());
+        assert_eq!(
+            vec![(NUM, "2.5E+10", Span::new(0, 7))],
+            lex("2.5E+10").unwrap()
+        );
+        assert_eq!(vec![(NUM, "0e0", Span::new(0, 3))], lex("0e0").unwrap());
+        assert_eq!(
+            vec![
+                (NUM, "1e3", Span::new(0, 3)),
+                (PLUS, "+", Span::new(3, 4)),
+                (NUM, "1", Span::new(4, 5))
+            ],
+            lex("1e3+1").unwrap()
+        );
+    }
+
+    #[test]
+    fn panic_at_incomplete_float() {
+        assert_eq!(
+            Err(Error::Lexical {
+                span: Span::new(1, 2),
+                found: '.'
+            }),
+            lex("1.")
+        );
+        assert_eq!(
+            Err(Error::Lexical {
+                span: Span::new(2, 3),
+                found: '-'
+            }),
+            lex("1e-")
+        );
+        assert!(lex(".5").is_err());
+        assert!(lex("1.2.3").is_err());
+        assert!(lex("1e2.5").is_err());
+        assert!(lex("1e*2").is_err());
+        assert_eq!(
+            Err(Error::Lexical {
+                span: Span::new(1, 2),
+                found: '.'
+            }),
+            lex("1. + 2")
+        );
+        assert_eq!(
+            Err(Error::Lexical {
+                span: Span::new(1, 2),
+                found: 'e'
+            }),
+            lex("1e*2")
+        );
     }
 
     #[test]
@@ -222,5 +492,229 @@ mod tests {
         assert!(lex("+001").is_err());
         assert!(lex("-001").is_err());
         assert!(lex("001").is_err());
+        assert_eq!(
+            Err(Error::Lexical {
+                span: Span::new(1, 2),
+                found: '0'
+            }),
+            lex("00")
+        );
+    }
+
+    #[test]
+    fn panic_at_unknown_character() {
+        assert_eq!(
+            Err(Error::Lexical {
+                span: Span::new(2, 3),
+                found: '#'
+            }),
+            lex("1 #")
+        );
+        assert_eq!(
+            Err(Error::Lexical {
+                span: Span::new(1, 3),
+                found: '×'
+            }),
+            lex("2×3")
+        );
+    }
+
+    #[test]
+    fn allow_identifier() {
+        assert_eq!(
+            vec![
+                (IDENT, "price", Span::new(0, 5)),
+                (STAR, "*", Span::new(6, 7)),
+                (IDENT, "qty_2", Span::new(8, 13))
+            ],
+            lex("price * qty_2").unwrap()
+        );
+        assert_eq!(vec![(IDENT, "e", Span::new(0, 1))], lex("e").unwrap());
+        assert_eq!(
+            vec![
+                (NUM, "1", Span::new(0, 1)),
+                (MINUS, "-", Span::new(1, 2)),
+                (IDENT, "_x", Span::new(2, 4))
+            ],
+            lex("1-_x").unwrap()
+        );
+    }
+
+    #[test]
+    fn panic_at_number_followed_by_letter() {
+        assert_eq!(
+            Err(Error::Lexical {
+                span: Span::new(1, 2),
+                found: 'x'
+            }),
+            lex("2x")
+        );
+        assert!(lex("1ex").is_err());
+        assert!(lex("1.5a").is_err());
+    }
+
+    #[test]
+    fn allow_radix_prefix() {
+        assert_eq!(
+            vec![
+                (NUM, "0xFF", Span::new(0, 4)),
+                (PLUS, "+", Span::new(4, 5)),
+                (NUM, "0b1010", Span::new(5, 11)),
+                (STAR, "*", Span::new(11, 12)),
+                (NUM, "0o17", Span::new(12, 16))
+            ],
+            lex("0xFF+0b1010*0o17").unwrap()
+        );
+        assert_eq!(
+            vec![(NUM, "0Xbeef", Span::new(0, 6))],
+            lex("0Xbeef").unwrap()
+        );
+    }
+
+    #[test]
+    fn allow_digit_separator() {
+        assert_eq!(
+            vec![(NUM, "1_000_000", Span::new(0, 9))],
+            lex("1_000_000").unwrap()
+        );
+        assert_eq!(
+            vec![(NUM, "1_000.000_5", Span::new(0, 11))],
+            lex("1_000.000_5").unwrap()
+        );
+        assert_eq!(
+            vec![(NUM, "0xFF_FF", Span::new(0, 7))],
+            lex("0xFF_FF").unwrap()
+        );
+        assert_eq!(vec![(NUM, "0b1_0", Span::new(0, 5))], lex("0b1_0").unwrap());
+    }
+
+    #[test]
+    fn panic_at_malformed_radix_or_separator() {
+        assert_eq!(
+            Err(Error::Lexical {
+                span: Span::new(2, 3),
+                found: '2'
+            }),
+            lex("0b2")
+        );
+        assert_eq!(
+            Err(Error::Lexical {
+                span: Span::new(2, 3),
+                found: '8'
+            }),
+            lex("0o8")
+        );
+        assert!(lex("0x").is_err());
+        // the unfinished prefix is blamed rather than what ends it
+        for code in ["0x + 1", "0x $", "0x+1"] {
+            assert_eq!(
+                Err(Error::Lexical {
+                    span: Span::new(1, 2),
+                    found: 'x'
+                }),
+                lex(code)
+            );
+        }
+        assert!(lex("0xG").is_err());
+        assert!(lex("0x_1").is_err());
+        assert!(lex("1_").is_err());
+        assert!(lex("1__0").is_err());
+        assert!(lex("1_.5").is_err());
+        assert!(lex("1._5").is_err());
+        assert!(lex("0_1").is_err());
+        assert!(lex("0x1.5").is_err());
+    }
+
+    #[test]
+    fn panic_at_empty_input() {
+        assert_eq!(Err(Error::EmptyInput), lex(""));
+        assert_eq!(Err(Error::EmptyInput), lex("   "));
+        assert_eq!(Err(Error::EmptyInput), lex(" \t\r\n\u{3000}"));
+    }
+
+    #[test]
+    fn allow_any_whitespace() {
+        assert_eq!(
+            vec![
+                (NUM, "1", Span::new(1, 2)),
+                (PLUS, "+", Span::new(3, 4)),
+                (NUM, "2", Span::new(6, 7)),
+                (STAR, "*", Span::new(10, 11)),
+                (NUM, "3", Span::new(12, 13))
+            ],
+            lex("\t1\n+\u{a0}2\u{3000}*\x0C3").unwrap()
+        );
+        // the whitespace ends a token, even in the middle of a number or a operator
+        assert_eq!(
+            vec![(NUM, "1", Span::new(0, 1)), (NUM, "2", Span::new(2, 3))],
+            lex("1\t2").unwrap()
+        );
+        assert_eq!(
+            vec![(STAR, "*", Span::new(0, 1)), (STAR, "*", Span::new(2, 3))],
+            lex("*\n*").unwrap()
+        );
+    }
+
+    #[test]
+    fn borrow_text_from_source() {
+        let code = "price * 1_000";
+        let tokens = lex(code).unwrap();
+        assert!(std::ptr::eq(&code[0..5], tokens[0].1));
+        assert!(std::ptr::eq(&code[8..13], tokens[2].1));
+
+        assert_eq!(
+            (NUM, "1_000".to_string(), Span::new(8, 13)),
+            to_owned_token(tokens[2])
+        );
+    }
+
+    #[test]
+    fn yield_tokens_lazily() {
+        let mut tokens = Tokenizer::new("1 + 2 # 3");
+        assert_eq!(Some(Ok((NUM, "1", Span::new(0, 1)))), tokens.next());
+        assert_eq!(Some(Ok((PLUS, "+", Span::new(2, 3)))), tokens.next());
+        assert_eq!(Some(Ok((NUM, "2", Span::new(4, 5)))), tokens.next());
+        assert_eq!(
+            Some(Err(Error::Lexical {
+                span: Span::new(6, 7),
+                found: '#'
+            })),
+            tokens.next()
+        );
+        assert_eq!(None, tokens.next());
+
+        // the operators split from a run are yielded one by one
+        let mut tokens = Tokenizer::new("**-");
+        assert_eq!(
+            Some(Ok((DOUBLE_STAR, "**", Span::new(0, 2)))),
+            tokens.next()
+        );
+        assert_eq!(Some(Ok((MINUS, "-", Span::new(2, 3)))), tokens.next());
+        assert_eq!(None, tokens.next());
+
+        let mut tokens = Tokenizer::new(" ");
+        assert_eq!(Some(Err(Error::EmptyInput)), tokens.next());
+        assert_eq!(None, tokens.next());
+    }
+
+    #[test]
+    fn allow_crlf() {
+        assert_eq!(
+            vec![
+                (NUM, "1", Span::new(0, 1)),
+                (PLUS, "+", Span::new(2, 3)),
+                (NUM, "2", Span::new(5, 6)),
+                (STAR, "*", Span::new(9, 10)),
+                (NUM, "3", Span::new(11, 12))
+            ],
+            lex("1 +\r\n2\r\n\t* 3\r\n").unwrap()
+        );
+        assert_eq!(
+            Err(Error::Lexical {
+                span: Span::new(5, 6),
+                found: '#'
+            }),
+            lex("1 +\r\n# 3")
+        );
     }
 }
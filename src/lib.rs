@@ -1,19 +1,67 @@
+mod bigint;
+mod error;
 mod lexer;
 mod parser;
-mod syntax_kind;
+mod rational;
+mod span;
+pub mod syntax_kind;
 mod traversal;
 
-use lexer::lex;
-use parser::{syntax, Node};
+use parser::{syntax, syntax_prefix};
 
-pub use traversal::{eval, format};
+pub use bigint::{BigInt, ParseBigIntError};
+pub use error::{Error, EvalError, EvalErrorKind};
+pub use lexer::{lex, to_owned_token, OwnedToken, Token, TokenStream, Tokenizer};
+pub use parser::{parser_combinator, Node, Num, MAX_DEPTH};
+pub use rational::Rational;
+pub use span::{line_col, Span};
+pub use syntax_kind::SyntaxKind;
+pub use traversal::{
+    eval, eval_as, eval_f64, eval_value, eval_with, eval_with_env, eval_with_registry, format,
+    format_with, try_eval, Arity, DivisionMode, EvalOptions, FormatOptions, Number, OverflowMode,
+    Radix, Registry, Resolver, Value, ValueType,
+};
 
 /// build a AST from a expression
-pub fn build_ast(expr: &str) -> Result<Node, String> {
-    let root = syntax(lex(expr)?)?;
+///
+/// the whole expression must be consumed, otherwise an `Error::TrailingInput` is returned
+pub fn build_ast(expr: &str) -> Result<Node, Error> {
+    let root = syntax(&lex(expr)?)?;
     Ok(root)
 }
 
+/// build a AST from the longest prefix of a expression which forms a complete expression
+///
+/// return the AST and the byte offset where the parsing stopped,
+/// the rest of the expression (`&expr[offset..]`) is left untouched,
+/// so it can contain characters which are not accepted by the lexer
+///
+/// # Example
+/// ```rust
+/// let expr = "1 + 2 ; 3";
+/// let (ast, offset) = build_ast_prefix(expr).unwrap();
+/// assert_eq!(3, eval(&ast));
+/// assert_eq!("; 3", &expr[offset..]);
+/// ```
+pub fn build_ast_prefix(expr: &str) -> Result<(Node, usize), Error> {
+    let tokens = match lex(expr) {
+        Ok(tokens) => tokens,
+        // the prefix may end right before the bad character
+        Err(err @ Error::Lexical { span, .. }) => match lex(&expr[..span.start]) {
+            Ok(tokens) => return parse_prefix(&tokens, span.start).map_err(|_| err),
+            Err(_) => return Err(err),
+        },
+        Err(err) => return Err(err),
+    };
+    parse_prefix(&tokens, expr.len())
+}
+
+fn parse_prefix(tokens: &[Token], end: usize) -> Result<(Node, usize), Error> {
+    let (root, rest) = syntax_prefix(tokens)?;
+    let offset = rest.first().map_or(end, |(_, _, span)| span.start);
+    Ok((root, offset))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -25,4 +73,369 @@ mod tests {
         assert_eq!(-1, eval(&ast));
         assert_eq!("1 * 2 + 3 / (4 + (-5))", format(&ast));
     }
+
+    #[test]
+    fn distinguish_errors() {
+        assert!(matches!(build_ast("1 + #"), Err(Error::Lexical { .. })));
+        assert!(matches!(
+            build_ast("(1 + 2"),
+            Err(Error::UnexpectedEof { .. })
+        ));
+        assert!(matches!(
+            build_ast("1 + * 2"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+        assert!(matches!(build_ast(" "), Err(Error::EmptyInput)));
+        assert!(matches!(
+            build_ast("1 + 2 )"),
+            Err(Error::TrailingInput { .. })
+        ));
+    }
+
+    #[test]
+    fn checked_eval() {
+        let ast = build_ast("1 + 2 / (3 - 3)").unwrap();
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::DivisionByZero,
+                Span::new(4, 15)
+            )),
+            try_eval(&ast)
+        );
+        assert_eq!(Ok(-1), try_eval(&build_ast("1*2+(3/(4+(-5)))").unwrap()));
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn eval_panics_at_error() {
+        eval(&build_ast("1 / 0").unwrap());
+    }
+
+    #[test]
+    fn float() {
+        let ast = build_ast("1.5 * 2").unwrap();
+        assert_eq!(3.0, eval_f64(&ast));
+        assert_eq!("1.5 * 2", format(&ast));
+        assert!(try_eval(&ast).is_err());
+    }
+
+    #[test]
+    fn generic_number() {
+        let ast = build_ast("3000000000 * 2").unwrap();
+        assert_eq!(
+            Ok(6000000000_i64),
+            eval_as::<i64>(&ast, EvalOptions::default())
+        );
+        assert_eq!(Ok(6e9), eval_as::<f64>(&ast, EvalOptions::default()));
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::InvalidLiteral,
+                Span::new(0, 10)
+            )),
+            try_eval(&ast)
+        );
+    }
+
+    #[test]
+    fn big_integer() {
+        let ast = build_ast("99999999999 * 99999999999").unwrap();
+        assert_eq!(
+            "9999999999800000000001",
+            eval_as::<BigInt>(&ast, EvalOptions::default())
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::InvalidLiteral,
+                Span::new(0, 11)
+            )),
+            try_eval(&ast)
+        );
+    }
+
+    #[test]
+    fn radix_literal() {
+        let ast = build_ast("0xFF & 0b1111_0000").unwrap();
+        assert_eq!(0xF0, eval(&ast));
+        assert_eq!("0xFF & 0b1111_0000", format(&ast));
+        assert_eq!(
+            "255 & 240",
+            format_with(
+                &ast,
+                FormatOptions {
+                    radix: Radix::Decimal
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn reuse_parser_combinator() {
+        use crate::parser_combinator::{between, eof, sep_by, single_token, Parser};
+        use crate::syntax_kind::{CLOSE_PAREN, COMMA, NUM, OPEN_PAREN};
+
+        // Tuple -> "(" (NUM ("," NUM)*)? ")" EOF
+        let tuple = || {
+            between(
+                single_token(OPEN_PAREN),
+                sep_by(single_token(NUM), single_token(COMMA)),
+                single_token(CLOSE_PAREN),
+            )
+            .and_then(|items| eof().map(move |_| items.len()))
+        };
+        let tokens = lex("(1, 2, 3)").unwrap();
+        assert_eq!(3, tuple().parse(&tokens).unwrap().1);
+        let tokens = lex("()").unwrap();
+        assert_eq!(0, tuple().parse(&tokens).unwrap().1);
+        let tokens = lex("(1, 2,)").unwrap();
+        let err = tuple().parse(&tokens).unwrap_err();
+        assert_eq!(vec![NUM], err.expected);
+        assert_eq!(Span::new(6, 7), err.input[0].2);
+    }
+
+    #[test]
+    fn parse_characters() {
+        use crate::parser_combinator::{chainr1, map, one_or_more, satisfy, tag, Parser};
+
+        // Power -> DIGIT+ ("**" Power)?
+        let digits = || {
+            map(one_or_more(satisfy(char::is_ascii_digit)), |digits| {
+                digits
+                    .into_iter()
+                    .collect::<String>()
+                    .parse::<u32>()
+                    .unwrap()
+            })
+        };
+        let power = chainr1(digits(), tag("**"), |base: u32, _, exp| base.pow(exp));
+        assert_eq!(Ok((" + 1", 512)), power.parse("2**3**2 + 1"));
+        let err = power.parse("2**").unwrap_err();
+        assert_eq!("", err.input);
+    }
+
+    #[test]
+    fn parse_custom_tokens() {
+        use crate::parser_combinator::{chainl1, either, tag, Parser, TokenLike};
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        enum Tok {
+            Num(i64),
+            Plus,
+            Minus,
+        }
+        impl TokenLike for Tok {
+            type Kind = &'static str;
+            fn kind(&self) -> &'static str {
+                match self {
+                    Tok::Num(_) => "number",
+                    Tok::Plus => "+",
+                    Tok::Minus => "-",
+                }
+            }
+        }
+
+        let tokens = [Tok::Num(1), Tok::Minus, Tok::Num(2), Tok::Plus, Tok::Num(3)];
+        let partial = [Tok::Num(1), Tok::Minus, Tok::Minus];
+
+        // Sum -> Num (("+" | "-") Num)*
+        let num = || {
+            tag("number").map(|tok| match tok {
+                Tok::Num(n) => n,
+                _ => unreachable!(),
+            })
+        };
+        let sum = chainl1(
+            num(),
+            either(tag("+"), tag("-")),
+            |left, op, right| match op {
+                Tok::Plus => left + right,
+                _ => left - right,
+            },
+        );
+        assert_eq!(Ok((&[][..], 2)), sum.parse(&tokens[..]));
+        let err = sum.parse(&partial[..]).unwrap_err();
+        assert_eq!(vec!["number"], err.expected);
+    }
+
+    #[test]
+    fn rational() {
+        let ast = build_ast("1 / 3 * 3").unwrap();
+        assert_eq!(0, eval(&ast));
+        let value = eval_as::<Rational>(&ast, EvalOptions::default()).unwrap();
+        assert_eq!("1", value.to_string());
+
+        let ast = build_ast("19.99 * 3 / 7").unwrap();
+        let value = eval_as::<Rational>(&ast, EvalOptions::default()).unwrap();
+        assert_eq!("5997/700", value.to_string());
+        assert_eq!("8.57", value.to_decimal(2));
+
+        let ast = build_ast("(2 / 3) ** -2").unwrap();
+        let value = eval_as::<Rational>(&ast, EvalOptions::default()).unwrap();
+        assert_eq!("9/4", value.to_string());
+
+        let ast = build_ast("7.5 % 2 + 7.5 // 2").unwrap();
+        let value = eval_as::<Rational>(&ast, EvalOptions::default()).unwrap();
+        assert_eq!("9/2", value.to_string());
+
+        let ast = build_ast("2 ** 0.5").unwrap();
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::NonIntegerExponent,
+                Span::new(0, 8)
+            )),
+            eval_as::<Rational>(&ast, EvalOptions::default())
+        );
+
+        let ast = build_ast("1 / (0.5 - 1 / 2)").unwrap();
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::DivisionByZero,
+                Span::new(0, 17)
+            )),
+            eval_as::<Rational>(&ast, EvalOptions::default())
+        );
+    }
+
+    #[test]
+    fn variable() {
+        let ast = build_ast("price * qty + shipping").unwrap();
+        assert_eq!("price * qty + shipping", format(&ast));
+
+        let mut env = std::collections::HashMap::new();
+        env.insert(
+            "price".to_string(),
+            Rational::from_literal("19.99").unwrap(),
+        );
+        env.insert("qty".to_string(), Rational::from(3));
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::UnboundVariable,
+                Span::new(14, 22)
+            )),
+            eval_with_env(&ast, &env, EvalOptions::default())
+        );
+
+        env.insert("shipping".to_string(), Rational::from(5));
+        let value = eval_with_env(&ast, &env, EvalOptions::default()).unwrap();
+        assert_eq!("64.97", value.to_decimal(2));
+    }
+
+    #[test]
+    fn call() {
+        let ast = build_ast("max(1, -2) + min(3,+4)").unwrap();
+        assert_eq!("max(1, -2) + min(3, +4)", format(&ast));
+
+        let functions = Registry::with_builtins();
+        assert_eq!(
+            Ok(4),
+            eval_with_registry(&ast, &(), &functions, EvalOptions::default())
+        );
+        assert!(matches!(
+            build_ast("max(1, )"),
+            Err(Error::UnexpectedToken { .. })
+        ));
+        assert!(matches!(
+            build_ast("max(1"),
+            Err(Error::UnexpectedEof { .. })
+        ));
+    }
+
+    #[test]
+    fn feature_flag_rule() {
+        let ast = build_ast("(a + 1) * 2 >= 10 && b != 0").unwrap();
+        assert_eq!("(a + 1) * 2 >= 10 && b != 0", format(&ast));
+
+        let env = std::collections::HashMap::from([("a", 4), ("b", 1)]);
+        assert_eq!(
+            Ok(Value::Bool(true)),
+            eval_value(&ast, &env, EvalOptions::default())
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::TypeMismatch {
+                    expected: ValueType::Number,
+                    found: ValueType::Bool
+                },
+                Span::new(0, 27)
+            )),
+            eval_with_env(&ast, &env, EvalOptions::default())
+        );
+
+        // `b` is never looked up when `a` already decides the result
+        let env = std::collections::HashMap::from([("a", 0)]);
+        assert_eq!(
+            Ok(Value::Bool(false)),
+            eval_value(&ast, &env, EvalOptions::default())
+        );
+    }
+
+    #[test]
+    fn pricing_tier() {
+        let ast = build_ast("qty > 100 ? price * 0.9 : price").unwrap();
+        let price = |qty: i64| {
+            let env = std::collections::HashMap::from([
+                ("qty", Rational::from(qty)),
+                ("price", Rational::from(20)),
+            ]);
+            eval_with_env(&ast, &env, EvalOptions::default()).map(|value| value.to_string())
+        };
+        assert_eq!(Ok("18".to_string()), price(150));
+        assert_eq!(Ok("20".to_string()), price(100));
+        assert!(matches!(
+            build_ast("qty > 100 ? price"),
+            Err(Error::UnexpectedEof { .. })
+        ));
+    }
+
+    #[test]
+    fn register_mask() {
+        let ast = build_ast("(1 << 4) | 15 & ~mask").unwrap();
+        assert_eq!("1 << 4 | 15 & ~mask", format(&ast));
+
+        let env = std::collections::HashMap::from([("mask", 0b0110)]);
+        assert_eq!(
+            Ok(0b1_1001),
+            eval_with_env(&ast, &env, EvalOptions::default())
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::ShiftOutOfRange,
+                Span::new(0, 7)
+            )),
+            try_eval(&build_ast("1 << 32").unwrap())
+        );
+        // the exact integer is not bounded by a width, but its size is
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::ShiftOutOfRange,
+                Span::new(0, 15)
+            )),
+            eval_as::<BigInt>(
+                &build_ast("1 << 4000000000").unwrap(),
+                EvalOptions::default()
+            )
+        );
+    }
+
+    #[test]
+    fn prefix() {
+        let expr = "1 + 2 ) 3";
+        let (ast, offset) = build_ast_prefix(expr).unwrap();
+        assert_eq!(3, eval(&ast));
+        assert_eq!(") 3", &expr[offset..]);
+
+        let expr = "1 + 2 ; 3";
+        let (ast, offset) = build_ast_prefix(expr).unwrap();
+        assert_eq!(3, eval(&ast));
+        assert_eq!("; 3", &expr[offset..]);
+
+        let (_, offset) = build_ast_prefix("1 + 2").unwrap();
+        assert_eq!(5, offset);
+
+        assert!(matches!(
+            build_ast_prefix("1 + #"),
+            Err(Error::Lexical { .. })
+        ));
+    }
 }
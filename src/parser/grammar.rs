@@ -1,110 +1,450 @@
 use crate::{
+    lexer::Token,
     parser::{
-        node::{Node, Node::*},
-        parser_combinator::{either, single_token, zero_or_more, Parser},
+        node::{Node, Node::*, Num},
+        parser_combinator::{map, single_token, ParseError, Parser, ParserResult, Tokens},
+    },
+    syntax_kind::{
+        SyntaxKind, ADD_EXPR, AND_EXPR, BIT_AND_EXPR, BIT_NOT_EXPR, BIT_OR_EXPR, BIT_XOR_EXPR,
+        CALL_EXPR, COND_EXPR, DIV_EXPR, EQ_EXPR, GE_EXPR, GT_EXPR, IDENT, INT_DIV_EXPR, LE_EXPR,
+        LT_EXPR, MUL_EXPR, NEG_EXPR, NE_EXPR, NOT_EXPR, NUM, OR_EXPR, POS_EXPR, POW_EXPR, REM_EXPR,
+        SHL_EXPR, SHR_EXPR, SUB_EXPR, UNKNOW,
     },
-    syntax_kind::{SyntaxKind, ADD_EXPR, DIV_EXPR, MUL_EXPR, NUM, SUB_EXPR, UNKNOW},
     token,
 };
 
+/// the most levels which a expression may be nested, a deeper one is rejected rather than overflowing the stack
+///
+/// every operand is one level deeper than its operator, except the left operand of a left associative
+/// operator which binds at least as tight, e.g. all the terms of `1 + 2 * 3 - 4` are at most one level deep,
+/// and the parentheses add no level, so the output of `format` always parses again
+///
+/// the parentheses, the arguments and the `then` branches may be nested this many times too,
+/// since each of them descends through the rules once more
+pub const MAX_DEPTH: usize = 256;
+
+/// a node with the level which it is nested to, see `MAX_DEPTH`
+///
+/// the node is boxed, so that the results passed between the nested rules stay small
+type Leveled = (Box<Node>, usize);
+
+/// the result of a rule which also tells the level of the node
+type LeveledResult<'input> = ParserResult<Tokens<'input>, Leveled>;
+
 /// Literal -> NUM
-pub fn literal() -> impl Parser<'static, Node> {
-    single_token(NUM).map(|(_, value)| Literal {
+///
+/// an integer of any length in any radix is kept exactly, e.g. `0xFF`, `1_000`
+pub fn literal<'input>() -> impl Parser<Tokens<'input>, Node> {
+    map(single_token(NUM), |(_, value, span)| Literal {
         kind: NUM,
-        value: value.parse().unwrap(),
-        raw: value,
+        value: Num::from_literal(value),
+        raw: value.to_string(),
+        span,
     })
 }
 
-/// Expr -> Term (("+" | "-") Term)*
-pub fn expr() -> impl Parser<'static, Node> {
-    term().and_then(|left| {
-        zero_or_more(
-            either(single_token(token!["+"]), single_token(token!["-"]))
-                .and_then(|(op, _)| term().map(move |right| (op, right))),
-        )
-        .map(move |node_list| build_expr_node(left.to_owned(), node_list))
-    })
+/// the whole expression, which is not nested yet
+pub fn expr<'input>() -> impl Parser<Tokens<'input>, Node> {
+    |input| conditional(input, 0).map(|(rest, (node, _))| (rest, *node))
 }
 
-/// Term -> Factor (("*" | "/") Factor)*
-fn term() -> impl Parser<'static, Node> {
-    factor().and_then(|left| {
-        zero_or_more(
-            either(single_token(token!["*"]), single_token(token!["/"]))
-                .and_then(|(op, _)| factor().map(move |right| (op, right))),
-        )
-        .map(move |node_list| build_expr_node(left.to_owned(), node_list))
-    })
+// the rules below are nested in one another, each of them is a `Parser-Like` function
+// which parses the input at the depth, so no parser is made when it is reached,
+// and the stack grows as little as possible for every level
+
+/// Expr -> Or ("?" Expr ":" Expr)?
+///
+/// it has the lowest priority and is right associative,
+/// e.g. `a ? b : c ? d : e` is `a ? b : (c ? d : e)`,
+/// such a chain of the `otherwise` branches is parsed in a loop and folded from the right
+fn conditional(mut input: Tokens, depth: usize) -> LeveledResult {
+    // every condition with its `then` branch, and the input where the conditional starts
+    let mut branches = Vec::new();
+    loop {
+        let (rest, condition) = binary(input, depth)?;
+        match single_token(token!["?"]).parse(rest) {
+            Ok((rest, _)) => {
+                let (rest, then) = nested(rest, depth, conditional)?;
+                let (rest, _) = single_token(token![":"]).parse(rest)?;
+                branches.push((input, condition, then));
+                input = rest;
+            }
+            Err(_) => return Ok((rest, build_conditional(branches, condition)?)),
+        }
+    }
 }
 
-/// Factor -> Literal | "(" Expr ")"
-fn factor() -> impl Parser<'static, Node> {
-    either(
-        literal(),
-        single_token(token!["("])
-            .and_then(|_| expr())
-            .and_then(|node| single_token(token![")"]).map(move |_| node.to_owned())),
-    )
+/// fold the branches from the right, the innermost conditional takes the last `otherwise` branch
+fn build_conditional<'input>(
+    mut branches: Vec<(Tokens<'input>, Leveled, Leveled)>,
+    mut otherwise: Leveled,
+) -> Result<Leveled, ParseError<Tokens<'input>>> {
+    while let Some((start, (condition, condition_level), (then, then_level))) = branches.pop() {
+        let level = 1 + condition_level.max(then_level).max(otherwise.1);
+        let node = Conditional {
+            kind: COND_EXPR,
+            span: condition.span().merge(otherwise.0.span()),
+            condition,
+            then,
+            otherwise: otherwise.0,
+        };
+        otherwise = leveled(start, node, level)?;
+    }
+    Ok(otherwise)
 }
 
-/// build expression node recursively
-fn build_expr_node(expr: Node, mut node_list: Vec<(SyntaxKind, Node)>) -> Node {
-    match node_list.len() {
-        0 => expr,
-        _ => {
-            let (op, right) = node_list.pop().unwrap();
-            Expr {
+/// Or         -> And ("||" And)*
+/// And        -> BitOr ("&&" BitOr)*
+/// BitOr      -> BitXor ("|" BitXor)*
+/// BitXor     -> BitAnd ("^" BitAnd)*
+/// BitAnd     -> Equality ("&" Equality)*
+/// Equality   -> Relational (("==" | "!=") Relational)*
+/// Relational -> Shift (("<" | "<=" | ">" | ">=") Shift)*
+/// Shift      -> Additive (("<<" | ">>") Additive)*
+/// Additive   -> Term (("+" | "-") Term)*
+/// Term       -> Unary (("*" | "/" | "//" | "%") Unary)*
+///
+/// the levels of the left associative binary operators, from the priority 1 of `Or` to 10 of `Term`,
+/// the bitwise operators bind looser than the comparisons like C, e.g. `a & 1 == 1` is `a & (1 == 1)`
+///
+/// the `Top-Down Parsing` can not deal with Left Recursive grammar,
+/// however, the basic operations are inherently `Left Associative`,
+/// therefore, all the levels are parsed in one loop, which keeps the operands waiting for
+/// their right operand, and folds them from the left once a operator of no tighter priority follows,
+/// to make the AST grow to the left, e.g. `1 - 2 * 3 - 4` is `(1 - (2 * 3)) - 4`
+fn binary(input: Tokens, depth: usize) -> LeveledResult {
+    // every left operand with the input where it starts, and its operator of the priority
+    let mut pending = Vec::new();
+    let (mut start, mut rest) = (input, input);
+    loop {
+        let (next, operand) = unary(rest, depth)?;
+        let op = binary_operator(next);
+        let (operand_start, operand) = fold_binary(&mut pending, (start, operand), op)?;
+        match op {
+            Some((op, priority)) => {
+                pending.push((operand_start, operand, op, priority));
+                start = &next[1..];
+                rest = start;
+            }
+            None => return Ok((next, operand)),
+        }
+    }
+}
+
+/// the left associative binary operator which the input starts with, and its priority
+fn binary_operator(input: Tokens) -> Option<(SyntaxKind, usize)> {
+    let (op, _, _) = input.first()?;
+    match SyntaxKind::get_op_priority(op.into_str()) {
+        priority if priority < SyntaxKind::get_op_priority("**") => Some((*op, priority)),
+        _ => None,
+    }
+}
+
+/// fold the pending left operands into the right one, as long as they bind at least as tight as
+/// the next operator, or all of them at the end
+#[allow(clippy::type_complexity)]
+fn fold_binary<'input>(
+    pending: &mut Vec<(Tokens<'input>, Leveled, SyntaxKind, usize)>,
+    (mut start, mut right): (Tokens<'input>, Leveled),
+    next: Option<(SyntaxKind, usize)>,
+) -> Result<(Tokens<'input>, Leveled), ParseError<Tokens<'input>>> {
+    while let Some((_, _, _, priority)) = pending.last() {
+        if next.is_some_and(|(_, next)| next > *priority) {
+            break;
+        }
+        let (left_start, left, op, _) = pending.pop().unwrap();
+        right = build_binary(left_start, left, op, right)?;
+        start = left_start;
+    }
+    Ok((start, right))
+}
+
+/// build a binary expression of a left associative operator which starts at `start`,
+/// its left operand adds no level unless it binds looser and is wrapped in the parentheses, e.g. `(1 + 2) * 3`
+fn build_binary<'input>(
+    start: Tokens<'input>,
+    (left, left_level): Leveled,
+    op: SyntaxKind,
+    (right, right_level): Leveled,
+) -> Result<Leveled, ParseError<Tokens<'input>>> {
+    let wrapped = match &*left {
+        Expr { op: left_op, .. } => {
+            SyntaxKind::get_op_priority(left_op.into_str())
+                < SyntaxKind::get_op_priority(op.into_str())
+        }
+        Conditional { .. } => true,
+        _ => false,
+    };
+    let level = (left_level + wrapped as usize).max(right_level + 1);
+    leveled(start, build_binary_node(left, op, right), level)
+}
+
+/// Unary -> ("+" | "-" | "!" | "~") Unary | Power
+/// Power -> Factor ("**" Unary)?
+///
+/// the prefix operator binds tighter than any binary one except the power,
+/// e.g. `-1 * 2` is `(-1) * 2`, but `-2 ** 2` is `-(2 ** 2)`
+///
+/// the power is right associative and its right operand can be negative,
+/// e.g. `2 ** 3 ** 2` is `2 ** (3 ** 2)`, `2 ** -1`
+///
+/// a run of them is parsed in a loop, every factor is collected with the prefix operators before it,
+/// then they are folded from the right to make the AST grow to the right
+fn unary(mut input: Tokens, depth: usize) -> LeveledResult {
+    // every factor with the input where each of its prefix operators starts, and where itself starts
+    let mut operands = Vec::new();
+    loop {
+        let mut prefixes = Vec::new();
+        let expected = loop {
+            match one_of(&[token!["+"], token!["-"], token!["!"], token!["~"]]).parse(input) {
+                Ok((rest, _)) => {
+                    prefixes.push(input);
+                    input = rest;
+                }
+                Err(err) => break err,
+            }
+        };
+        let (rest, operand) = factor(input, depth).map_err(|err| expected.merge(err))?;
+        operands.push((prefixes, input, operand));
+        match single_token(token!["**"]).parse(rest) {
+            Ok((rest, _)) => input = rest,
+            Err(_) => return Ok((rest, build_power(operands)?)),
+        }
+    }
+}
+
+/// fold the operands of `**` from the right, each of them with its prefix operators
+fn build_power<'input>(
+    mut operands: Vec<(Vec<Tokens<'input>>, Tokens<'input>, Leveled)>,
+) -> Result<Leveled, ParseError<Tokens<'input>>> {
+    let (prefixes, _, right) = operands.pop().unwrap();
+    let mut right = build_prefix(prefixes, right)?;
+    while let Some((prefixes, start, (left, left_level))) = operands.pop() {
+        let level = 1 + left_level.max(right.1);
+        right = leveled(start, build_binary_node(left, token!["**"], right.0), level)?;
+        right = build_prefix(prefixes, right)?;
+    }
+    Ok(right)
+}
+
+/// apply the prefix operators to the operand from the innermost one,
+/// each of them is given as the input where it starts
+fn build_prefix<'input>(
+    prefixes: Vec<Tokens<'input>>,
+    operand: Leveled,
+) -> Result<Leveled, ParseError<Tokens<'input>>> {
+    prefixes
+        .into_iter()
+        .rev()
+        .try_fold(operand, |(operand, level), start| {
+            let (op, _, op_span) = start[0];
+            let node = Unary {
                 kind: match op {
-                    token!["+"] => ADD_EXPR,
-                    token!["-"] => SUB_EXPR,
-                    token!["*"] => MUL_EXPR,
-                    token!["/"] => DIV_EXPR,
-                    _ => UNKNOW,
+                    token!["-"] => NEG_EXPR,
+                    token!["!"] => NOT_EXPR,
+                    token!["~"] => BIT_NOT_EXPR,
+                    _ => POS_EXPR,
                 },
-                /// the `Top-Down Parsing` can not deal with Left Recursive grammar
-                /// however, the basic operations are inherently `Left Associative`
-                /// therefore, it must be recursive to the left to simulate left recursion when building nodes
-                /// just make the AST grow to the left
-                left: Box::new(build_expr_node(expr, node_list)),
                 op,
-                right: Box::new(right),
-            }
+                span: op_span.merge(operand.span()),
+                operand,
+            };
+            leveled(start, node, level + 1)
+        })
+}
+
+/// match any token of the kinds, and all of them are expected if none matches
+fn one_of<'input>(kinds: &'static [SyntaxKind]) -> impl Parser<Tokens<'input>, Token<'input>> {
+    move |input: Tokens<'input>| match input.first() {
+        Some(token) if kinds.contains(&token.0) => Ok((&input[1..], *token)),
+        _ => Err(kinds
+            .iter()
+            .fold(ParseError::new(input), |err, kind| err.expecting(*kind))),
+    }
+}
+
+/// Factor -> Literal | Variable | Call | "(" Expr ")"
+fn factor(input: Tokens, depth: usize) -> LeveledResult {
+    match input.first() {
+        Some((IDENT, _, _)) => variable_or_call(input, depth),
+        Some((token!["("], _, _)) => paren(input, depth),
+        _ => number(input),
+    }
+}
+
+/// a literal, which is the only other factor
+fn number(input: Tokens) -> LeveledResult {
+    map(literal(), |node| (Box::new(node), 0))
+        .parse(input)
+        .map_err(|err| err.expecting(IDENT).expecting(token!["("]))
+}
+
+/// "(" Expr ")", which adds no level
+fn paren(input: Tokens, depth: usize) -> LeveledResult {
+    nested(&input[1..], depth, conditional).and_then(|(rest, (node, level))| {
+        let (_, _, open) = input[0];
+        let (rest, (_, _, close)) = single_token(token![")"]).parse(rest)?;
+        Ok((rest, (Box::new(node.with_span(open.merge(close))), level)))
+    })
+}
+
+/// Variable -> IDENT
+/// Call     -> IDENT "(" (Expr ("," Expr)*)? ")"
+///
+/// a name followed by "(" is always a call, e.g. `f (1)` is not `f` times `(1)`
+fn variable_or_call(input: Tokens, depth: usize) -> LeveledResult {
+    let (rest, (_, name, span)) = single_token(IDENT).parse(input)?;
+    match single_token(token!["("]).parse(rest) {
+        Ok((rest, _)) => nested(rest, depth, arguments).and_then(|(rest, args)| {
+            let (rest, (_, _, close)) = single_token(token![")"]).parse(rest)?;
+            let level = 1 + args.iter().map(|(_, level)| *level).max().unwrap_or(0);
+            let call = Call {
+                kind: CALL_EXPR,
+                name: name.to_owned(),
+                args: args.into_iter().map(|(arg, _)| *arg).collect(),
+                span: span.merge(close),
+            };
+            Ok((rest, leveled(input, call, level)?))
+        }),
+        Err(_) => {
+            let variable = Variable {
+                kind: IDENT,
+                name: name.to_owned(),
+                span,
+            };
+            Ok((rest, (Box::new(variable), 0)))
+        }
+    }
+}
+
+/// Args -> (Expr ("," Expr)*)?
+fn arguments(input: Tokens, depth: usize) -> ParserResult<Tokens, Vec<Leveled>> {
+    let mut args = Vec::new();
+    let mut rest = match conditional(input, depth) {
+        Ok((rest, arg)) => {
+            args.push(arg);
+            rest
         }
+        Err(err) if err.too_deep || err.input.len() < input.len() => return Err(err),
+        Err(_) => return Ok((input, args)),
+    };
+    while let Ok((next, _)) = single_token(token![","]).parse(rest) {
+        let (next, arg) = conditional(next, depth)?;
+        args.push(arg);
+        rest = next;
+    }
+    Ok((rest, args))
+}
+
+/// parse the input by the rule one level deeper,
+/// or give up beyond `MAX_DEPTH` rather than descending through the rules once more
+fn nested<'input, Output>(
+    input: Tokens<'input>,
+    depth: usize,
+    rule: fn(Tokens<'input>, usize) -> ParserResult<Tokens<'input>, Output>,
+) -> ParserResult<Tokens<'input>, Output> {
+    match depth < MAX_DEPTH {
+        true => rule(input, depth + 1),
+        false => Err(ParseError::too_deep(input)),
+    }
+}
+
+/// the node with its level, or give up at `start` where it begins if it is beyond `MAX_DEPTH`
+fn leveled<'input>(
+    start: Tokens<'input>,
+    node: Node,
+    level: usize,
+) -> Result<Leveled, ParseError<Tokens<'input>>> {
+    match level <= MAX_DEPTH {
+        true => Ok((Box::new(node), level)),
+        false => Err(ParseError::too_deep(start)),
+    }
+}
+
+/// build a binary expression node whose span covers both operands
+fn build_binary_node(left: Box<Node>, op: SyntaxKind, right: Box<Node>) -> Node {
+    Expr {
+        kind: match op {
+            token!["+"] => ADD_EXPR,
+            token!["-"] => SUB_EXPR,
+            token!["*"] => MUL_EXPR,
+            token!["/"] => DIV_EXPR,
+            token!["**"] => POW_EXPR,
+            token!["//"] => INT_DIV_EXPR,
+            token!["%"] => REM_EXPR,
+            token!["=="] => EQ_EXPR,
+            token!["!="] => NE_EXPR,
+            token!["<"] => LT_EXPR,
+            token!["<="] => LE_EXPR,
+            token![">"] => GT_EXPR,
+            token![">="] => GE_EXPR,
+            token!["&&"] => AND_EXPR,
+            token!["||"] => OR_EXPR,
+            token!["&"] => BIT_AND_EXPR,
+            token!["|"] => BIT_OR_EXPR,
+            token!["^"] => BIT_XOR_EXPR,
+            token!["<<"] => SHL_EXPR,
+            token![">>"] => SHR_EXPR,
+            _ => UNKNOW,
+        },
+        span: left.span().merge(right.span()),
+        left,
+        op,
+        right,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bigint::BigInt;
     use crate::lex;
-    use crate::syntax_kind::{MINUS, PLUS, SLASH, STAR};
+    use crate::lexer::TokenStream;
+    use crate::span::Span;
+    use crate::syntax_kind::{
+        AMP, AMP_AMP, BANG, CARET, DOUBLE_STAR, EQ_EQ, LT, LT_LT, MINUS, PIPE, PIPE_PIPE, PLUS,
+        SLASH, STAR, TILDE,
+    };
 
+    /// spans are checked by `span_covers_paren`, the others only care about the shape of the tree
+    /// the rest of the input is copied, so that it can be compared with a `Vec`
+    fn without_span<'a, E>(
+        result: Result<(&'a [Token<'a>], Node), E>,
+    ) -> Result<(TokenStream<'a>, Node), E> {
+        result.map(|(input, node)| (input.to_vec(), node.without_span()))
+    }
+
+    #[allow(clippy::type_complexity)]
     fn get_number() -> (Box<Node>, Box<Node>, Box<Node>, Box<Node>, Box<Node>) {
         let one = Box::new(Literal {
             kind: NUM,
-            value: 1,
+            value: Num::Int(BigInt::from(1)),
             raw: "1".to_string(),
+            span: Span::default(),
         });
         let two = Box::new(Literal {
             kind: NUM,
-            value: 2,
+            value: Num::Int(BigInt::from(2)),
             raw: "2".to_string(),
+            span: Span::default(),
         });
         let three = Box::new(Literal {
             kind: NUM,
-            value: 3,
+            value: Num::Int(BigInt::from(3)),
             raw: "3".to_string(),
+            span: Span::default(),
         });
         let four = Box::new(Literal {
             kind: NUM,
-            value: 4,
+            value: Num::Int(BigInt::from(4)),
             raw: "4".to_string(),
+            span: Span::default(),
         });
         let five = Box::new(Literal {
             kind: NUM,
-            value: 5,
+            value: Num::Int(BigInt::from(5)),
             raw: "5".to_string(),
+            span: Span::default(),
         });
         (one, two, three, four, five)
     }
@@ -115,18 +455,21 @@ mod tests {
             left: one.clone(),
             op: PLUS,
             right: two.clone(),
+            span: Span::default(),
         });
         let two_plus_three = Box::new(Expr {
             kind: ADD_EXPR,
             left: two.clone(),
             op: PLUS,
             right: three.clone(),
+            span: Span::default(),
         });
         let three_plus_four = Box::new(Expr {
             kind: ADD_EXPR,
             left: three.clone(),
             op: PLUS,
             right: four.clone(),
+            span: Span::default(),
         });
         (one_plus_two, two_plus_three, three_plus_four)
     }
@@ -139,11 +482,12 @@ mod tests {
                 vec![],
                 Literal {
                     kind: NUM,
-                    value: 1,
-                    raw: "1".to_string()
+                    value: Num::Int(BigInt::from(1)),
+                    raw: "1".to_string(),
+                    span: Span::default(),
                 }
             )),
-            literal().parse(input)
+            without_span(literal().parse(&input))
         );
     }
 
@@ -160,10 +504,11 @@ mod tests {
                     kind: SUB_EXPR,
                     left: one_plus_two.clone(),
                     op: MINUS,
-                    right: three.clone()
+                    right: three.clone(),
+                    span: Span::default(),
                 }
             )),
-            expr().parse(input)
+            without_span(expr().parse(&input))
         );
         let input = lex("1 * 2 / 3").unwrap();
         assert_eq!(
@@ -175,13 +520,15 @@ mod tests {
                         kind: MUL_EXPR,
                         left: one.clone(),
                         op: STAR,
-                        right: two.clone()
+                        right: two.clone(),
+                        span: Span::default(),
                     }),
                     op: SLASH,
-                    right: three.clone()
+                    right: three.clone(),
+                    span: Span::default(),
                 }
             )),
-            expr().parse(input)
+            without_span(expr().parse(&input))
         );
 
         let input = lex("( 1 )").unwrap();
@@ -190,11 +537,12 @@ mod tests {
                 vec![],
                 Literal {
                     kind: NUM,
-                    value: 1,
-                    raw: "1".to_string()
+                    value: Num::Int(BigInt::from(1)),
+                    raw: "1".to_string(),
+                    span: Span::default(),
                 }
             )),
-            expr().parse(input)
+            without_span(expr().parse(&input))
         );
     }
 
@@ -215,11 +563,13 @@ mod tests {
                         kind: MUL_EXPR,
                         left: two.clone(),
                         op: STAR,
-                        right: three.clone()
-                    })
+                        right: three.clone(),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
                 }
             )),
-            expr().parse(input)
+            without_span(expr().parse(&input))
         );
 
         let input = lex("1 + 2 - 3 * 4").unwrap();
@@ -234,11 +584,13 @@ mod tests {
                         kind: MUL_EXPR,
                         left: three.clone(),
                         op: STAR,
-                        right: four.clone()
-                    })
+                        right: four.clone(),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
                 }
             )),
-            expr().parse(input)
+            without_span(expr().parse(&input))
         );
 
         let input = lex("1 + 2 * 3 * 4").unwrap();
@@ -255,14 +607,17 @@ mod tests {
                             kind: MUL_EXPR,
                             left: two.clone(),
                             op: STAR,
-                            right: three.clone()
+                            right: three.clone(),
+                            span: Span::default(),
                         }),
                         op: STAR,
-                        right: four.clone()
-                    })
+                        right: four.clone(),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
                 }
             )),
-            expr().parse(input)
+            without_span(expr().parse(&input))
         );
     }
 
@@ -283,11 +638,13 @@ mod tests {
                         kind: DIV_EXPR,
                         left: two.clone(),
                         op: SLASH,
-                        right: three.clone()
-                    })
+                        right: three.clone(),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
                 }
             )),
-            expr().parse(input)
+            without_span(expr().parse(&input))
         );
 
         let input = lex("1 + 2 - 3 / 4").unwrap();
@@ -302,11 +659,13 @@ mod tests {
                         kind: DIV_EXPR,
                         left: three.clone(),
                         op: SLASH,
-                        right: four.clone()
-                    })
+                        right: four.clone(),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
                 }
             )),
-            expr().parse(input)
+            without_span(expr().parse(&input))
         );
 
         let input = lex("1 + 2 / 3 / 4").unwrap();
@@ -323,14 +682,17 @@ mod tests {
                             kind: DIV_EXPR,
                             left: two.clone(),
                             op: SLASH,
-                            right: three.clone()
+                            right: three.clone(),
+                            span: Span::default(),
                         }),
                         op: SLASH,
-                        right: four.clone()
-                    })
+                        right: four.clone(),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
                 }
             )),
-            expr().parse(input)
+            without_span(expr().parse(&input))
         );
     }
 
@@ -347,10 +709,11 @@ mod tests {
                     kind: MUL_EXPR,
                     left: one.clone(),
                     op: STAR,
-                    right: two_plus_three.clone()
+                    right: two_plus_three.clone(),
+                    span: Span::default(),
                 }
             )),
-            expr().parse(input)
+            without_span(expr().parse(&input))
         );
     }
 
@@ -371,11 +734,476 @@ mod tests {
                         kind: ADD_EXPR,
                         left: two.clone(),
                         op: PLUS,
-                        right: three_plus_four.clone()
-                    })
+                        right: three_plus_four.clone(),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
+                }
+            )),
+            without_span(expr().parse(&input))
+        );
+    }
+
+    #[test]
+    fn span_covers_paren() {
+        let input = lex("1 * (2 + 3)").unwrap();
+        let (_, node) = expr().parse(&input).unwrap();
+        assert_eq!(Span::new(0, 11), node.span());
+        match node {
+            Expr { left, right, .. } => {
+                assert_eq!(Span::new(0, 1), left.span());
+                assert_eq!(Span::new(4, 11), right.span());
+            }
+            _ => unreachable!(),
+        }
+
+        let input = lex("( 1 )").unwrap();
+        let (_, node) = expr().parse(&input).unwrap();
+        assert_eq!(Span::new(0, 5), node.span());
+    }
+
+    #[test]
+    fn float_literal() {
+        let input = lex("1.5e2").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Literal {
+                    kind: NUM,
+                    value: Num::Float(150.0),
+                    raw: "1.5e2".to_string(),
+                    span: Span::default(),
+                }
+            )),
+            without_span(literal().parse(&input))
+        );
+    }
+
+    #[test]
+    fn test_variable() {
+        let (one, _, _, _, _) = get_number();
+
+        let input = lex("1 * ( rate )").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Expr {
+                    kind: MUL_EXPR,
+                    left: one.clone(),
+                    op: STAR,
+                    right: Box::new(Variable {
+                        kind: IDENT,
+                        name: "rate".to_string(),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
+                }
+            )),
+            without_span(expr().parse(&input))
+        );
+    }
+
+    #[test]
+    fn test_call() {
+        let (one, two, three, _, _) = get_number();
+        let (_, two_plus_three, _) = get_expr();
+
+        let input = lex("max(1, 2 + 3) * min()").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Expr {
+                    kind: MUL_EXPR,
+                    left: Box::new(Call {
+                        kind: CALL_EXPR,
+                        name: "max".to_string(),
+                        args: vec![*one.clone(), *two_plus_three.clone()],
+                        span: Span::default(),
+                    }),
+                    op: STAR,
+                    right: Box::new(Call {
+                        kind: CALL_EXPR,
+                        name: "min".to_string(),
+                        args: vec![],
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
+                }
+            )),
+            without_span(expr().parse(&input))
+        );
+
+        let input = lex("f(g(2), (3))").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Call {
+                    kind: CALL_EXPR,
+                    name: "f".to_string(),
+                    args: vec![
+                        Call {
+                            kind: CALL_EXPR,
+                            name: "g".to_string(),
+                            args: vec![*two.clone()],
+                            span: Span::default(),
+                        },
+                        *three.clone()
+                    ],
+                    span: Span::default(),
+                }
+            )),
+            without_span(expr().parse(&input))
+        );
+
+        let input = lex("abs(-1) + 2").unwrap();
+        let (_, node) = expr().parse(&input).unwrap();
+        match node {
+            Expr { left, .. } => assert_eq!(Span::new(0, 7), left.span()),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_unary() {
+        let (one, two, _, _, _) = get_number();
+        let (one_plus_two, _, _) = get_expr();
+
+        let input = lex("-(1 + 2) * - + 2").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Expr {
+                    kind: MUL_EXPR,
+                    left: Box::new(Unary {
+                        kind: NEG_EXPR,
+                        op: MINUS,
+                        operand: one_plus_two.clone(),
+                        span: Span::default(),
+                    }),
+                    op: STAR,
+                    right: Box::new(Unary {
+                        kind: NEG_EXPR,
+                        op: MINUS,
+                        operand: Box::new(Unary {
+                            kind: POS_EXPR,
+                            op: PLUS,
+                            operand: two.clone(),
+                            span: Span::default(),
+                        }),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
+                }
+            )),
+            without_span(expr().parse(&input))
+        );
+
+        let input = lex("1 - -1").unwrap();
+        let (_, node) = expr().parse(&input).unwrap();
+        assert_eq!(
+            Expr {
+                kind: SUB_EXPR,
+                left: one.clone(),
+                op: MINUS,
+                right: Box::new(Unary {
+                    kind: NEG_EXPR,
+                    op: MINUS,
+                    operand: one.clone(),
+                    span: Span::default(),
+                }),
+                span: Span::default(),
+            },
+            node.clone().without_span()
+        );
+        match node {
+            Expr { right, .. } => assert_eq!(Span::new(4, 6), right.span()),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn power_is_right_associative() {
+        let (_, two, three, _, _) = get_number();
+
+        let input = lex("-2 ** 3 ** 2").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Unary {
+                    kind: NEG_EXPR,
+                    op: MINUS,
+                    operand: Box::new(Expr {
+                        kind: POW_EXPR,
+                        left: two.clone(),
+                        op: DOUBLE_STAR,
+                        right: Box::new(Expr {
+                            kind: POW_EXPR,
+                            left: three.clone(),
+                            op: DOUBLE_STAR,
+                            right: two.clone(),
+                            span: Span::default(),
+                        }),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
+                }
+            )),
+            without_span(expr().parse(&input))
+        );
+
+        let input = lex("2 * 3 ** -2").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Expr {
+                    kind: MUL_EXPR,
+                    left: two.clone(),
+                    op: STAR,
+                    right: Box::new(Expr {
+                        kind: POW_EXPR,
+                        left: three.clone(),
+                        op: DOUBLE_STAR,
+                        right: Box::new(Unary {
+                            kind: NEG_EXPR,
+                            op: MINUS,
+                            operand: two.clone(),
+                            span: Span::default(),
+                        }),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
+                }
+            )),
+            without_span(expr().parse(&input))
+        );
+    }
+
+    #[test]
+    fn comparison_and_logic_precedence() {
+        let (one, two, three, four, _) = get_number();
+        let (one_plus_two, _, _) = get_expr();
+
+        let input = lex("!1 == 2 || 1 + 2 < 3 && 4").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Expr {
+                    kind: OR_EXPR,
+                    left: Box::new(Expr {
+                        kind: EQ_EXPR,
+                        left: Box::new(Unary {
+                            kind: NOT_EXPR,
+                            op: BANG,
+                            operand: one.clone(),
+                            span: Span::default(),
+                        }),
+                        op: EQ_EQ,
+                        right: two.clone(),
+                        span: Span::default(),
+                    }),
+                    op: PIPE_PIPE,
+                    right: Box::new(Expr {
+                        kind: AND_EXPR,
+                        left: Box::new(Expr {
+                            kind: LT_EXPR,
+                            left: one_plus_two.clone(),
+                            op: LT,
+                            right: three.clone(),
+                            span: Span::default(),
+                        }),
+                        op: AMP_AMP,
+                        right: four.clone(),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
+                }
+            )),
+            without_span(expr().parse(&input))
+        );
+        // the comparisons are left associative like the others, `1 < 2 < 3` is `(1 < 2) < 3`
+        let input = lex("1 < 2 < 3").unwrap();
+        assert!(matches!(
+            expr().parse(&input),
+            Ok((rest, Expr { kind: LT_EXPR, left, .. })) if rest.is_empty() && matches!(*left, Expr { kind: LT_EXPR, .. })
+        ));
+    }
+
+    #[test]
+    fn bitwise_precedence_like_c() {
+        let (one, two, three, four, five) = get_number();
+
+        // `1 | 2 ^ 3 & 4 == 5` is `1 | (2 ^ (3 & (4 == 5)))`
+        let input = lex("1 | 2 ^ 3 & 4 == 5").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Expr {
+                    kind: BIT_OR_EXPR,
+                    left: one.clone(),
+                    op: PIPE,
+                    right: Box::new(Expr {
+                        kind: BIT_XOR_EXPR,
+                        left: two.clone(),
+                        op: CARET,
+                        right: Box::new(Expr {
+                            kind: BIT_AND_EXPR,
+                            left: three.clone(),
+                            op: AMP,
+                            right: Box::new(Expr {
+                                kind: EQ_EXPR,
+                                left: four.clone(),
+                                op: EQ_EQ,
+                                right: five.clone(),
+                                span: Span::default(),
+                            }),
+                            span: Span::default(),
+                        }),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
+                }
+            )),
+            without_span(expr().parse(&input))
+        );
+
+        // `~1 << 2 + 3 < 4` is `((~1) << (2 + 3)) < 4`
+        let input = lex("~1 << 2 + 3 < 4").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Expr {
+                    kind: LT_EXPR,
+                    left: Box::new(Expr {
+                        kind: SHL_EXPR,
+                        left: Box::new(Unary {
+                            kind: BIT_NOT_EXPR,
+                            op: TILDE,
+                            operand: one.clone(),
+                            span: Span::default(),
+                        }),
+                        op: LT_LT,
+                        right: Box::new(Expr {
+                            kind: ADD_EXPR,
+                            left: two.clone(),
+                            op: PLUS,
+                            right: three.clone(),
+                            span: Span::default(),
+                        }),
+                        span: Span::default(),
+                    }),
+                    op: LT,
+                    right: four.clone(),
+                    span: Span::default(),
+                }
+            )),
+            without_span(expr().parse(&input))
+        );
+    }
+
+    #[test]
+    fn conditional_is_right_associative() {
+        let (one, two, three, four, five) = get_number();
+        let (one_plus_two, _, _) = get_expr();
+
+        let input = lex("1 || 2 ? 1 + 2 : 3 ? 4 : 5").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Conditional {
+                    kind: COND_EXPR,
+                    condition: Box::new(Expr {
+                        kind: OR_EXPR,
+                        left: one.clone(),
+                        op: PIPE_PIPE,
+                        right: two.clone(),
+                        span: Span::default(),
+                    }),
+                    then: one_plus_two.clone(),
+                    otherwise: Box::new(Conditional {
+                        kind: COND_EXPR,
+                        condition: three.clone(),
+                        then: four.clone(),
+                        otherwise: five.clone(),
+                        span: Span::default(),
+                    }),
+                    span: Span::default(),
+                }
+            )),
+            without_span(expr().parse(&input))
+        );
+
+        // the branch between `?` and `:` can be a conditional too
+        let input = lex("1 ? 2 ? 3 : 4 : 5").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Conditional {
+                    kind: COND_EXPR,
+                    condition: one.clone(),
+                    then: Box::new(Conditional {
+                        kind: COND_EXPR,
+                        condition: two.clone(),
+                        then: three.clone(),
+                        otherwise: four.clone(),
+                        span: Span::default(),
+                    }),
+                    otherwise: five.clone(),
+                    span: Span::default(),
+                }
+            )),
+            without_span(expr().parse(&input))
+        );
+
+        let input = lex("(1 ? 2 : 3) * 4").unwrap();
+        assert_eq!(Span::new(0, 15), expr().parse(&input).unwrap().1.span());
+        let input = lex("1 ? 2").unwrap();
+        assert!(expr().parse(&input).is_err());
+    }
+
+    #[test]
+    fn huge_literal() {
+        let input = lex("99999999999999999999999999999999999999999").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Literal {
+                    kind: NUM,
+                    value: Num::Int("99999999999999999999999999999999999999999".parse().unwrap()),
+                    raw: "99999999999999999999999999999999999999999".to_string(),
+                    span: Span::default(),
+                }
+            )),
+            without_span(literal().parse(&input))
+        );
+    }
+
+    #[test]
+    fn radix_literal() {
+        let input = lex("0xE").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Literal {
+                    kind: NUM,
+                    value: Num::Int(14.into()),
+                    raw: "0xE".to_string(),
+                    span: Span::default(),
+                }
+            )),
+            without_span(literal().parse(&input))
+        );
+        let input = lex("1_000.000_5").unwrap();
+        assert_eq!(
+            Ok((
+                vec![],
+                Literal {
+                    kind: NUM,
+                    value: Num::Float(1000.0005),
+                    raw: "1_000.000_5".to_string(),
+                    span: Span::default(),
                 }
             )),
-            expr().parse(input)
+            without_span(literal().parse(&input))
         );
     }
 }
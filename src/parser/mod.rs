@@ -1,28 +1,200 @@
 use crate::{
-    lexer::TokenStream,
+    error::Error,
+    lexer::Token,
     parser::{grammar::expr, parser_combinator::Parser},
+    span::Span,
 };
 
 mod grammar;
 mod node;
-mod parser_combinator;
+/// the parser combinators which the grammar is built with,
+/// they work over any `Input`: a slice of the tokens from `lex`, so a small DSL can share the tokenizer,
+/// a slice of the tokens from another lexer which implement `TokenLike`, or a `&str` of characters
+///
+/// # Example
+/// ```rust
+/// // Tuple -> "(" (NUM ("," NUM)*)? ")" EOF
+/// let tuple = between(
+///     single_token(token!["("]),
+///     sep_by(single_token(NUM), single_token(token![","])),
+///     single_token(token![")"]),
+/// )
+/// .and_then(|items| eof().map(move |_| items.len()));
+/// assert_eq!(3, tuple.parse(&lex("(1, 2, 3)").unwrap()).unwrap().1);
+///
+/// // Digits -> [0-9]+ "%"
+/// let percent = pair(one_or_more(satisfy(char::is_ascii_digit)), tag("%"));
+/// assert_eq!(Ok(("", (vec!['5', '0'], "%"))), percent.parse("50%"));
+/// ```
+pub mod parser_combinator;
 
-pub use node::Node;
+pub use grammar::MAX_DEPTH;
+pub(crate) use node::split_radix;
+pub use node::{Node, Num};
 
 /// Parser
-/// build a AST from a token stream
+/// build a AST from a token stream, all the tokens must be consumed
 /// return a `Result` to indicate whether it is successful or not
 /// - Ok(Root)
-/// - Err(ErrorMessage)
-pub fn syntax(tokens: TokenStream) -> Result<Node, String> {
+/// - Err(Error)
+pub fn syntax(tokens: &[Token]) -> Result<Node, Error> {
+    let (root, rest) = syntax_prefix(tokens)?;
+    match rest.first() {
+        Some((_, text, span)) => Err(Error::TrailingInput {
+            span: *span,
+            found: text.to_string(),
+        }),
+        None => Ok(root),
+    }
+}
+
+/// build a AST from the longest prefix of a token stream which forms a complete expression
+/// return a `Result` to indicate whether it is successful or not
+/// - Ok((Root, Remaining Tokens))
+/// - Err(Error)
+pub fn syntax_prefix<'a, 'src>(
+    tokens: &'a [Token<'src>],
+) -> Result<(Node, &'a [Token<'src>]), Error> {
+    if tokens.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    // the end of the input, which is where an unexpected EOF is reported
+    let end = tokens.last().map_or(0, |(_, _, span)| span.end);
     match expr().parse(tokens) {
-        Ok((_, n)) => Ok(n),
-        Err(output) => Err(format!(
-            "panic at parsing `{}`",
-            output.iter().fold(String::new(), |mut res, (_, cur)| {
-                res.push_str(cur);
-                res
-            })
-        )),
+        Ok((rest, n)) => Ok((n, &tokens[tokens.len() - rest.len()..])),
+        Err(err) if err.too_deep => Err(Error::TooDeep {
+            span: err
+                .input
+                .first()
+                .map_or(Span::new(end, end), |(_, _, span)| *span),
+        }),
+        Err(err) => Err(match err.input.first() {
+            Some((_, text, span)) => Error::UnexpectedToken {
+                span: *span,
+                found: text.to_string(),
+                expected: err.expected,
+            },
+            None => Error::UnexpectedEof {
+                span: Span::new(end, end),
+                expected: err.expected,
+            },
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::parser::{syntax_prefix, MAX_DEPTH};
+    use crate::span::Span;
+    use crate::syntax_kind::{BANG, CLOSE_PAREN, IDENT, MINUS, NUM, OPEN_PAREN, PLUS, TILDE};
+    use crate::{lex, syntax};
+
+    #[test]
+    fn unexpected_token() {
+        assert_eq!(
+            Err(Error::UnexpectedToken {
+                span: Span::new(4, 5),
+                found: ")".to_string(),
+                expected: vec![PLUS, MINUS, BANG, TILDE, NUM, IDENT, OPEN_PAREN]
+            }),
+            syntax(&lex("1 + )").unwrap())
+        );
+        assert_eq!(
+            Err(Error::UnexpectedToken {
+                span: Span::new(0, 1),
+                found: "*".to_string(),
+                expected: vec![PLUS, MINUS, BANG, TILDE, NUM, IDENT, OPEN_PAREN]
+            }),
+            syntax(&lex("* 1").unwrap())
+        );
+    }
+
+    #[test]
+    fn unexpected_eof() {
+        assert_eq!(
+            Err(Error::UnexpectedEof {
+                span: Span::new(3, 3),
+                expected: vec![PLUS, MINUS, BANG, TILDE, NUM, IDENT, OPEN_PAREN]
+            }),
+            syntax(&lex("1 +").unwrap())
+        );
+        assert_eq!(
+            Err(Error::UnexpectedEof {
+                span: Span::new(6, 6),
+                expected: vec![CLOSE_PAREN]
+            }),
+            syntax(&lex("(1 + 2").unwrap())
+        );
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(Err(Error::EmptyInput), syntax(&[]));
+    }
+
+    #[test]
+    fn trailing_input() {
+        assert_eq!(
+            Err(Error::TrailingInput {
+                span: Span::new(6, 7),
+                found: ")".to_string()
+            }),
+            syntax(&lex("1 + 2 )").unwrap())
+        );
+        assert_eq!(
+            Err(Error::TrailingInput {
+                span: Span::new(4, 5),
+                found: "3".to_string()
+            }),
+            syntax(&lex("(1) 3").unwrap())
+        );
+    }
+
+    #[test]
+    fn prefix() {
+        let tokens = lex("(1) 3").unwrap();
+        let (_, rest) = syntax_prefix(&tokens).unwrap();
+        assert_eq!(&[(NUM, "3", Span::new(4, 5))], rest);
+
+        let tokens = lex("1 + 2").unwrap();
+        let (_, rest) = syntax_prefix(&tokens).unwrap();
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn too_deep() {
+        let parens = |n: usize| format!("{}1{}", "(".repeat(n), ")".repeat(n));
+        assert!(syntax(&lex(&parens(MAX_DEPTH)).unwrap()).is_ok());
+        assert_eq!(
+            Err(Error::TooDeep {
+                span: Span::new(MAX_DEPTH + 1, MAX_DEPTH + 2)
+            }),
+            syntax(&lex(&parens(MAX_DEPTH + 1)).unwrap())
+        );
+
+        // the nesting of a long formula is still far below the limit
+        for code in [
+            parens(200),
+            format!("{}x", "- ".repeat(17)),
+            format!("{}x", "a ? 1 : ".repeat(33)),
+            format!("2{}", " ** 2".repeat(MAX_DEPTH)),
+            format!("1{}", " + 2 * 3".repeat(MAX_DEPTH * 4)),
+        ] {
+            assert!(syntax(&lex(&code).unwrap()).is_ok(), "{}", code);
+        }
+
+        // far beyond the limit, which would overflow the stack without it
+        for code in [
+            parens(100_000),
+            format!("{}1", "-".repeat(100_000)),
+            format!("2{}", " ** 2".repeat(100_000)),
+            format!("{}1{}", "f(".repeat(100_000), ")".repeat(100_000)),
+            format!("{}1", "1 ? 1 : ".repeat(100_000)),
+            "(".repeat(100_000),
+        ] {
+            let err = syntax(&lex(&code).unwrap()).unwrap_err();
+            assert!(matches!(err, Error::TooDeep { .. }), "{}", err);
+        }
     }
 }
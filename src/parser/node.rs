@@ -1,12 +1,85 @@
+use crate::bigint::BigInt;
+use crate::span::Span;
 use crate::syntax_kind::SyntaxKind;
 
+/// the value of a number literal
+#[derive(Debug, Clone, PartialOrd, PartialEq)]
+pub enum Num {
+    /// literal without fraction and exponent, e.g. `42`
+    ///
+    /// held exactly, the numeric type of the evaluation decides whether it fits
+    Int(BigInt),
+    /// literal with fraction or exponent, e.g. `1.5`, `1e-3`
+    Float(f64),
+}
+
+impl Num {
+    /// parse the text of a NUM token, which the lexer guarantees to be a valid number
+    ///
+    /// the digits of a radix literal are converted only once, e.g. `0xFF` to `255`
+    pub(crate) fn from_literal(raw: &str) -> Num {
+        match split_radix(raw) {
+            (10, _) => {
+                let text = raw.replace('_', "");
+                match text.contains(['.', 'e', 'E']) {
+                    true => Num::Float(text.parse().unwrap()),
+                    false => Num::Int(text.parse().unwrap()),
+                }
+            }
+            (radix, digits) => {
+                Num::Int(BigInt::from_str_radix(&digits.replace('_', ""), radix).unwrap())
+            }
+        }
+    }
+}
+
+/// split a literal into its radix and the digits after the prefix, e.g. `(16, "FF")` for `0xFF`
+pub(crate) fn split_radix(raw: &str) -> (u32, &str) {
+    match raw.get(..2) {
+        Some("0x" | "0X") => (16, &raw[2..]),
+        Some("0o" | "0O") => (8, &raw[2..]),
+        Some("0b" | "0B") => (2, &raw[2..]),
+        _ => (10, raw),
+    }
+}
+
 /// enumerate the structure of all ast nodes
+///
+/// every node records the span of the source it was built from,
+/// including the parentheses surrounding it
 #[derive(Debug, Clone, PartialOrd, PartialEq)]
 pub enum Node {
+    /// raw: the text in the source, e.g. `0xFF`, `1_000`, which is kept for formatting
     Literal {
         kind: SyntaxKind,
-        value: i32,
+        value: Num,
         raw: String,
+        span: Span,
+    },
+
+    /// a name whose value is supplied by the caller at evaluation time
+    Variable {
+        kind: SyntaxKind,
+        name: String,
+        span: Span,
+    },
+
+    /// a function applied to the comma-separated arguments, e.g. `max(a, b)`
+    ///
+    /// the function is looked up by name at evaluation time
+    Call {
+        kind: SyntaxKind,
+        name: String,
+        args: Vec<Node>,
+        span: Span,
+    },
+
+    /// a prefix operator applied to the operand, e.g. `-x`, `+(1 + 2)`
+    Unary {
+        kind: SyntaxKind,
+        op: SyntaxKind,
+        operand: Box<Node>,
+        span: Span,
     },
 
     Expr {
@@ -14,5 +87,94 @@ pub enum Node {
         left: Box<Node>,
         op: SyntaxKind,
         right: Box<Node>,
+        span: Span,
+    },
+
+    /// choose one of the branches by the condition, e.g. `a > b ? a : b`
+    ///
+    /// only the chosen branch is evaluated
+    Conditional {
+        kind: SyntaxKind,
+        condition: Box<Node>,
+        then: Box<Node>,
+        otherwise: Box<Node>,
+        span: Span,
     },
 }
+
+impl Node {
+    pub fn span(&self) -> Span {
+        match self {
+            Node::Literal { span, .. }
+            | Node::Variable { span, .. }
+            | Node::Call { span, .. }
+            | Node::Unary { span, .. }
+            | Node::Expr { span, .. }
+            | Node::Conditional { span, .. } => *span,
+        }
+    }
+    /// replace the span of the node, e.g. to cover the surrounding parentheses
+    pub fn with_span(mut self, new_span: Span) -> Node {
+        match &mut self {
+            Node::Literal { span, .. }
+            | Node::Variable { span, .. }
+            | Node::Call { span, .. }
+            | Node::Unary { span, .. }
+            | Node::Expr { span, .. }
+            | Node::Conditional { span, .. } => *span = new_span,
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+impl Node {
+    /// erase all the spans in the tree, to compare the shape of trees only
+    pub(crate) fn without_span(self) -> Node {
+        match self {
+            Node::Expr {
+                kind,
+                left,
+                op,
+                right,
+                ..
+            } => Node::Expr {
+                kind,
+                left: Box::new(left.without_span()),
+                op,
+                right: Box::new(right.without_span()),
+                span: Span::default(),
+            },
+            Node::Call {
+                kind, name, args, ..
+            } => Node::Call {
+                kind,
+                name,
+                args: args.into_iter().map(Node::without_span).collect(),
+                span: Span::default(),
+            },
+            Node::Unary {
+                kind, op, operand, ..
+            } => Node::Unary {
+                kind,
+                op,
+                operand: Box::new(operand.without_span()),
+                span: Span::default(),
+            },
+            Node::Conditional {
+                kind,
+                condition,
+                then,
+                otherwise,
+                ..
+            } => Node::Conditional {
+                kind,
+                condition: Box::new(condition.without_span()),
+                then: Box::new(then.without_span()),
+                otherwise: Box::new(otherwise.without_span()),
+                span: Span::default(),
+            },
+            _ => self.with_span(Span::default()),
+        }
+    }
+}
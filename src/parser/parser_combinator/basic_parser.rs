@@ -1,45 +1,113 @@
-use crate::lexer::{Token, TokenStream};
-use crate::parser::parser_combinator::combinator::judge;
-use crate::parser::parser_combinator::traits::Parser;
+use crate::lexer::Token;
+use crate::parser::parser_combinator::input::{Input, Tokens};
+use crate::parser::parser_combinator::traits::{ParseError, Parser};
 use crate::syntax_kind::SyntaxKind;
 
-pub fn atom<'input>() -> impl Parser<'input, Token> {
-    move |input: TokenStream| {
-        let mut it = input.iter();
-        match it.next() {
-            Some(next) => Ok((
-                input.iter().skip(1).map(|t| t.to_owned()).collect(),
-                next.to_owned(),
-            )),
-            None => Err(input),
-        }
+/// match any one item, e.g. a token or a char
+pub fn atom<I: Input>() -> impl Parser<I, I::Item> {
+    move |input: I| match input.split_first() {
+        Some((next, rest)) => Ok((rest, next)),
+        None => Err(ParseError::new(input)),
     }
 }
 
-pub fn single_token(expect: SyntaxKind) -> impl Parser<'static, Token> {
-    judge(atom(), move |(kind, _)| *kind == expect)
+/// match one item which is accepted by the `predicate`, e.g. `satisfy(char::is_ascii_digit)`
+pub fn satisfy<I, F>(predicate: F) -> impl Parser<I, I::Item>
+where
+    I: Input,
+    F: Fn(&I::Item) -> bool,
+{
+    move |input: I| match input.split_first() {
+        Some((next, rest)) if predicate(&next) => Ok((rest, next)),
+        _ => Err(ParseError::new(input)),
+    }
+}
+
+/// match the tag, e.g. a token of the kind or a prefix of the string,
+/// which is expected if it does not match
+pub fn tag<I: Input>(expect: I::Tag) -> impl Parser<I, I::Matched> {
+    move |input: I| match input.split_tag(expect) {
+        Some((matched, rest)) => Ok((rest, matched)),
+        None => Err(ParseError::new(input).expecting(expect)),
+    }
+}
+
+/// match a token of the kind, which is expected if it does not match
+pub fn single_token<'input>(expect: SyntaxKind) -> impl Parser<Tokens<'input>, Token<'input>> {
+    tag(expect)
+}
+
+/// match the end of the input, without consuming anything
+pub fn eof<I: Input>() -> impl Parser<I, ()> {
+    move |input: I| match input.input_len() {
+        0 => Ok((input, ())),
+        _ => Err(ParseError::new(input)),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::span::Span;
     use crate::syntax_kind::{NUM, PLUS};
 
     #[test]
     fn test_atom() {
-        let input = vec![(NUM, "1".to_string()), (NUM, "2".to_string())];
+        let input = [(NUM, "1", Span::default()), (NUM, "2", Span::default())];
         assert_eq!(
-            Ok((vec![(NUM, "2".to_string())], (NUM, "1".to_string()))),
-            atom().parse(input)
+            Ok((&input[1..], (NUM, "1", Span::default()))),
+            atom().parse(&input[..])
         );
+        let empty: Tokens = &[];
+        assert_eq!(Err(ParseError::new(empty)), atom().parse(empty));
     }
 
     #[test]
     fn test_single_token() {
-        let input = vec![(PLUS, "+".to_string())];
+        let input = [(PLUS, "+", Span::default())];
+        assert_eq!(
+            Ok((&input[1..], (PLUS, "+", Span::default()))),
+            single_token(PLUS).parse(&input[..])
+        );
+    }
+
+    #[test]
+    fn single_token_reports_expected_kind() {
+        let input = [(NUM, "1", Span::default())];
+        assert_eq!(
+            Err(ParseError::new(&input[..]).expecting(PLUS)),
+            single_token(PLUS).parse(&input[..])
+        );
+    }
+
+    #[test]
+    fn test_eof() {
+        let input = [(NUM, "1", Span::default())];
+        assert_eq!(Ok((&input[1..], ())), eof().parse(&input[1..]));
+        assert_eq!(Err(ParseError::new(&input[..])), eof().parse(&input[..]));
+    }
+
+    #[test]
+    fn test_satisfy() {
+        let input = [(NUM, "1", Span::default())];
         assert_eq!(
-            Ok((vec![], (PLUS, "+".to_string()))),
-            single_token(PLUS).parse(input)
-        )
+            Ok((&input[1..], (NUM, "1", Span::default()))),
+            satisfy(|(kind, _, _)| *kind == NUM).parse(&input[..])
+        );
+        assert_eq!(Ok(("+2", '1')), satisfy(char::is_ascii_digit).parse("1+2"));
+        assert_eq!(
+            Err(ParseError::new("+2")),
+            satisfy(char::is_ascii_digit).parse("+2")
+        );
+    }
+
+    #[test]
+    fn test_tag() {
+        assert_eq!(Ok(("2", "**")), tag("**").parse("**2"));
+        assert_eq!(
+            Err(ParseError::new("*2").expecting("**")),
+            tag("**").parse("*2")
+        );
+        assert_eq!(Ok(("", ())), eof().parse(""));
     }
 }
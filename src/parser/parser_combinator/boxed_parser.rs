@@ -1,15 +1,16 @@
-use crate::lexer::TokenStream;
+use crate::parser::parser_combinator::input::Input;
 use crate::parser::parser_combinator::traits::ParserResult;
 use crate::parser::parser_combinator::Parser;
 
-pub struct BoxedParser<'input, Output> {
-    pub(crate) parser: Box<dyn Parser<'input, Output> + 'input>,
+/// a parser of any type behind a pointer, which is returned by the chained calls of `Parser`
+pub struct BoxedParser<'a, I, Output> {
+    parser: Box<dyn Parser<I, Output> + 'a>,
 }
 
-impl<'input, Output> BoxedParser<'input, Output> {
+impl<'a, I: Input, Output> BoxedParser<'a, I, Output> {
     pub fn new<P>(parser: P) -> Self
     where
-        P: Parser<'input, Output> + 'input,
+        P: Parser<I, Output> + 'a,
     {
         BoxedParser {
             parser: Box::new(parser),
@@ -19,8 +20,8 @@ impl<'input, Output> BoxedParser<'input, Output> {
 
 /// implement the `Parser` trait for the `BoxedParser`
 /// to support chained calls
-impl<'input, Output> Parser<'input, Output> for BoxedParser<'input, Output> {
-    fn parse(&self, input: TokenStream) -> ParserResult<Output> {
+impl<I: Input, Output> Parser<I, Output> for BoxedParser<'_, I, Output> {
+    fn parse(&self, input: I) -> ParserResult<I, Output> {
         self.parser.parse(input)
     }
 }
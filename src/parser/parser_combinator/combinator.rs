@@ -1,12 +1,13 @@
-use crate::lexer::TokenStream;
-use crate::parser::parser_combinator::traits::Parser;
+use crate::parser::parser_combinator::input::Input;
+use crate::parser::parser_combinator::traits::{ParseError, Parser, ParserResult};
 
-pub fn map<'input, P, Output, MapFn, NewOutput>(
+/// transform the output of the parser
+pub fn map<I: Input, P, Output, MapFn, NewOutput>(
     parser: P,
     map_fn: MapFn,
-) -> impl Parser<'input, NewOutput>
+) -> impl Parser<I, NewOutput>
 where
-    P: Parser<'input, Output>,
+    P: Parser<I, Output>,
     MapFn: Fn(Output) -> NewOutput,
 {
     move |input| {
@@ -16,14 +17,15 @@ where
     }
 }
 
-pub fn and_then<'input, CurParser, CurOutput, NextFn, NextParser, NextOutput>(
+/// match the parser, then the one made from its output
+pub fn and_then<I: Input, CurParser, CurOutput, NextFn, NextParser, NextOutput>(
     cur_parser: CurParser,
     next_fn: NextFn,
-) -> impl Parser<'input, NextOutput>
+) -> impl Parser<I, NextOutput>
 where
-    CurParser: Parser<'input, CurOutput>,
+    CurParser: Parser<I, CurOutput>,
     NextFn: Fn(CurOutput) -> NextParser,
-    NextParser: Parser<'input, NextOutput>,
+    NextParser: Parser<I, NextOutput>,
 {
     move |input| match cur_parser.parse(input) {
         Ok((next_input, cur_output)) => match next_fn(cur_output).parse(next_input) {
@@ -34,105 +36,572 @@ where
     }
 }
 
-pub fn judge<'input, P, Output, JudgeFn>(
-    parser: P,
-    judge_fn: JudgeFn,
-) -> impl Parser<'input, Output>
+/// match the parsers in sequence, and keep both outputs
+///
+/// unlike `and_then`, the first output is moved into the result rather than captured,
+/// so it is never cloned, e.g. `pair(factor(), optional(power))`
+pub fn pair<I: Input, P1, P2, Output1, Output2>(
+    parser1: P1,
+    parser2: P2,
+) -> impl Parser<I, (Output1, Output2)>
+where
+    P1: Parser<I, Output1>,
+    P2: Parser<I, Output2>,
+{
+    move |input: I| {
+        let (next_input, output1) = parser1.parse(input)?;
+        let (final_input, output2) = parser2.parse(next_input)?;
+        Ok((final_input, (output1, output2)))
+    }
+}
+
+/// match the parser only if its output is accepted by the `judge_fn`
+pub fn judge<I: Input, P, Output, JudgeFn>(parser: P, judge_fn: JudgeFn) -> impl Parser<I, Output>
 where
-    P: Parser<'input, Output>,
+    P: Parser<I, Output>,
     JudgeFn: Fn(&Output) -> bool,
 {
-    move |input: TokenStream| match parser.parse(input.clone()) {
+    move |input: I| match parser.parse(input) {
         Ok((next_input, output)) if judge_fn(&output) => Ok((next_input, output)),
-        _ => Err(input),
+        Ok(_) => Err(ParseError::new(input)),
+        Err(err) => Err(err),
     }
 }
 
-pub fn either<'input, P1, P2, Output>(parser1: P1, parser2: P2) -> impl Parser<'input, Output>
+/// match the first parser, or the second one if the first fails
+///
+/// the farthest error is kept if both fail, and the second one is not tried if the first is too deep
+pub fn either<I: Input, P1, P2, Output>(parser1: P1, parser2: P2) -> impl Parser<I, Output>
 where
-    P1: Parser<'input, Output>,
-    P2: Parser<'input, Output>,
+    P1: Parser<I, Output>,
+    P2: Parser<I, Output>,
 {
-    move |input: TokenStream| match parser1.parse(input.clone()) {
+    move |input: I| match parser1.parse(input) {
         Ok((next_input, output)) => Ok((next_input, output)),
-        Err(_) => parser2.parse(input),
+        Err(err1) if err1.too_deep => Err(err1),
+        Err(err1) => match parser2.parse(input) {
+            Ok((next_input, output)) => Ok((next_input, output)),
+            Err(err2) => Err(err1.merge(err2)),
+        },
     }
 }
 
-pub fn zero_or_more<'input, P, Output>(parser: P) -> impl Parser<'input, Vec<Output>>
+/// match the parser repeatedly, and collect all the outputs
+pub fn zero_or_more<I: Input, P, Output>(parser: P) -> impl Parser<I, Vec<Output>>
 where
-    P: Parser<'input, Output>,
+    P: Parser<I, Output>,
 {
-    move |mut input: TokenStream| {
+    move |input: I| {
         let mut result = Vec::new();
-        while let Ok((next_input, item)) = parser.parse(input.clone()) {
-            input = next_input;
-            result.push(item)
-        }
+        let input = many(|input| parser.parse(input), input, &mut result)?;
+        Ok((input, result))
+    }
+}
+
+/// like `zero_or_more`, but the parser must match at least once
+pub fn one_or_more<I: Input, P, Output>(parser: P) -> impl Parser<I, Vec<Output>>
+where
+    P: Parser<I, Output>,
+{
+    move |input: I| {
+        let (input, first) = parser.parse(input)?;
+        let mut result = vec![first];
+        let input = many(|input| parser.parse(input), input, &mut result)?;
+        Ok((input, result))
+    }
+}
+
+/// match zero or more items separated by the separator, e.g. `1, 2, 3`
+///
+/// a trailing separator is an error, e.g. `1, 2,`
+pub fn sep_by<I: Input, P, S, Output, SepOutput>(item: P, sep: S) -> impl Parser<I, Vec<Output>>
+where
+    P: Parser<I, Output>,
+    S: Parser<I, SepOutput>,
+{
+    move |input: I| {
+        let (input, first) = match item.parse(input) {
+            Ok(matched) => matched,
+            Err(err) if err.too_deep || err.input.input_len() < input.input_len() => {
+                return Err(err)
+            }
+            Err(_) => return Ok((input, Vec::new())),
+        };
+        let mut result = vec![first];
+        let input = many(
+            |input| {
+                sep.parse(input)
+                    .and_then(|(next_input, _)| item.parse(next_input))
+            },
+            input,
+            &mut result,
+        )?;
         Ok((input, result))
     }
 }
 
+/// match the parser between the `open` and the `close`, and keep only its output
+///
+/// # Example
+/// ```rust
+/// let paren = between(single_token(token!["("]), expr(), single_token(token![")"]));
+/// ```
+pub fn between<I: Input, O, P, C, OpenOutput, Output, CloseOutput>(
+    open: O,
+    parser: P,
+    close: C,
+) -> impl Parser<I, Output>
+where
+    O: Parser<I, OpenOutput>,
+    P: Parser<I, Output>,
+    C: Parser<I, CloseOutput>,
+{
+    map(pair(open, pair(parser, close)), |(_, (output, _))| output)
+}
+
+/// Operand (Op Operand)*
+///
+/// a chain of left associative binary operators, e.g. `1 - 2 - 3` is `(1 - 2) - 3`,
+/// the `fold_fn` combines the left operand, the operator and the right operand
+pub fn chainl1<I: Input, P, O, Output, Op, FoldFn>(
+    operand: P,
+    op: O,
+    fold_fn: FoldFn,
+) -> impl Parser<I, Output>
+where
+    P: Parser<I, Output>,
+    O: Parser<I, Op>,
+    FoldFn: Fn(Output, Op, Output) -> Output,
+{
+    map(chain(operand, op), move |(first, rest)| {
+        rest.into_iter()
+            .fold(first, |left, (op, right)| fold_fn(left, op, right))
+    })
+}
+
+/// Operand (Op Operand)*
+///
+/// a chain of right associative binary operators, e.g. `2 ** 3 ** 2` is `2 ** (3 ** 2)`,
+/// the `fold_fn` combines the left operand, the operator and the right operand
+pub fn chainr1<I: Input, P, O, Output, Op, FoldFn>(
+    operand: P,
+    op: O,
+    fold_fn: FoldFn,
+) -> impl Parser<I, Output>
+where
+    P: Parser<I, Output>,
+    O: Parser<I, Op>,
+    FoldFn: Fn(Output, Op, Output) -> Output,
+{
+    map(chain(operand, op), move |(first, rest)| {
+        let (ops, mut operands): (Vec<_>, Vec<_>) = rest.into_iter().unzip();
+        operands.insert(0, first);
+        let mut right = operands.pop().unwrap();
+        for op in ops.into_iter().rev() {
+            right = fold_fn(operands.pop().unwrap(), op, right);
+        }
+        right
+    })
+}
+
+/// the operands and the operators of a chain, which are folded by `chainl1` or `chainr1`
+fn chain<I: Input, P, O, Output, Op>(
+    operand: P,
+    op: O,
+) -> impl Parser<I, (Output, Vec<(Op, Output)>)>
+where
+    P: Parser<I, Output>,
+    O: Parser<I, Op>,
+{
+    move |input: I| {
+        let (input, first) = operand.parse(input)?;
+        let mut rest = Vec::new();
+        let input = many(
+            |input| {
+                op.parse(input).and_then(|(next_input, op)| {
+                    operand
+                        .parse(next_input)
+                        .map(|(final_input, right)| (final_input, (op, right)))
+                })
+            },
+            input,
+            &mut rest,
+        )?;
+        Ok((input, (first, rest)))
+    }
+}
+
+/// keep matching until it fails without consuming anything, and return the remaining input
+fn many<I: Input, Output>(
+    parse: impl Fn(I) -> ParserResult<I, Output>,
+    mut input: I,
+    result: &mut Vec<Output>,
+) -> Result<I, ParseError<I>> {
+    loop {
+        match parse(input) {
+            Ok((next_input, item)) => {
+                input = next_input;
+                result.push(item)
+            }
+            // the item was partially matched before it failed,
+            // e.g. `1 + ` stops after the operator, which must not be ignored, neither must a too deep one
+            Err(err) if err.too_deep || err.input.input_len() < input.input_len() => {
+                return Err(err)
+            }
+            Err(_) => return Ok(input),
+        }
+    }
+}
+
+/// match the parser at most once
+///
+/// like `zero_or_more`, a partial match is an error rather than `None`
+pub fn optional<I: Input, P, Output>(parser: P) -> impl Parser<I, Option<Output>>
+where
+    P: Parser<I, Output>,
+{
+    move |input: I| match parser.parse(input) {
+        Ok((next_input, item)) => Ok((next_input, Some(item))),
+        Err(err) if err.too_deep || err.input.input_len() < input.input_len() => Err(err),
+        Err(_) => Ok((input, None)),
+    }
+}
+
+/// succeed without consuming anything only if the parser fails, e.g. a name not followed by `(`
+pub fn not_followed_by<I: Input, P, Output>(parser: P) -> impl Parser<I, ()>
+where
+    P: Parser<I, Output>,
+{
+    move |input: I| match parser.parse(input) {
+        Ok(_) => Err(ParseError::new(input)),
+        Err(_) => Ok((input, ())),
+    }
+}
+
+/// make the parser only when it is used, to refer to a parser recursively
+///
+/// # Example
+/// ```rust
+/// // Factor -> NUM | "(" Factor ")"
+/// fn factor<'input>() -> impl Parser<Tokens<'input>, Token<'input>> {
+///     either(
+///         single_token(NUM),
+///         between(single_token(token!["("]), lazy(factor), single_token(token![")"])),
+///     )
+/// }
+/// ```
+pub fn lazy<I: Input, MakeFn, P, Output>(make_fn: MakeFn) -> impl Parser<I, Output>
+where
+    MakeFn: Fn() -> P,
+    P: Parser<I, Output>,
+{
+    move |input: I| make_fn().parse(input)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser::parser_combinator::basic_parser::atom;
+    use crate::lexer::Token;
+    use crate::parser::parser_combinator::basic_parser::{atom, eof};
+    use crate::parser::parser_combinator::input::Tokens;
+    use crate::span::Span;
     use crate::syntax_kind::{NUM, PLUS};
 
     #[test]
     fn test_map() {
-        let input = vec![(NUM, "1".to_string()), (NUM, "2".to_string())];
+        let input = [(NUM, "1", Span::default()), (NUM, "2", Span::default())];
         assert_eq!(
-            Ok((vec![(NUM, "2".to_string())], (PLUS, "+".to_string()))),
-            map(atom(), |_| (PLUS, "+".to_string())).parse(input)
+            Ok((&input[1..], (PLUS, "+", Span::default()))),
+            map(atom(), |_| (PLUS, "+", Span::default())).parse(&input[..])
         );
     }
 
     #[test]
     fn test_and_then() {
-        let input = vec![(NUM, "1".to_string()), (NUM, "2".to_string())];
+        let input = [(NUM, "1", Span::default()), (NUM, "2", Span::default())];
         assert_eq!(
-            Ok((vec![], (NUM, "2".to_string()))),
-            and_then(atom(), |_| { atom() }).parse(input)
-        )
+            Ok((&input[2..], (NUM, "2", Span::default()))),
+            and_then(atom(), |_| { atom() }).parse(&input[..])
+        );
+    }
+
+    #[test]
+    fn test_pair() {
+        let input = [(NUM, "1", Span::default()), (PLUS, "+", Span::default())];
+        assert_eq!(
+            Ok((
+                &input[2..],
+                ((NUM, "1", Span::default()), (PLUS, "+", Span::default()))
+            )),
+            pair(atom(), atom()).parse(&input[..])
+        );
+        assert_eq!(
+            Err(ParseError::new(&input[1..])),
+            pair(atom(), judge(atom(), |(kind, _, _)| *kind == NUM)).parse(&input[..])
+        );
     }
 
     #[test]
     fn test_judge() {
-        let input = vec![(PLUS, "+".to_string())];
+        let input = [(PLUS, "+", Span::default())];
+        assert_eq!(
+            Ok((&input[1..], (PLUS, "+", Span::default()))),
+            judge(atom(), |(kind, _, _)| *kind == PLUS).parse(&input[..])
+        );
         assert_eq!(
-            Ok((vec![], (PLUS, "+".to_string()))),
-            judge(atom(), |(kind, _)| *kind == PLUS).parse(input)
-        )
+            Err(ParseError::new(&input[..])),
+            judge(atom(), |(kind, _, _)| *kind == NUM).parse(&input[..])
+        );
     }
 
     #[test]
     fn test_either() {
-        let input = vec![(NUM, "1".to_string())];
-        let number_parser = judge(atom(), |(kind, _)| *kind == NUM);
-        let plus_parser = judge(atom(), |(kind, _)| *kind == PLUS);
+        let input = [(NUM, "1", Span::default())];
+        let number_parser = judge(atom(), |(kind, _, _)| *kind == NUM);
+        let plus_parser = judge(atom(), |(kind, _, _)| *kind == PLUS);
         assert_eq!(
-            Ok((vec![], (NUM, "1".to_string()))),
-            either(number_parser, plus_parser).parse(input)
-        )
+            Ok((&input[1..], (NUM, "1", Span::default()))),
+            either(number_parser, plus_parser).parse(&input[..])
+        );
     }
 
     #[test]
     fn test_zero_or_more() {
-        let num_one = (NUM, "1".to_string());
+        let num_one = (NUM, "1", Span::default());
 
-        let num_parser = judge(atom(), |(kind, text)| *kind == NUM && text == "1");
-        let input = vec![];
-        assert_eq!(Ok((vec![], vec![])), zero_or_more(num_parser).parse(input));
+        let num_parser = judge(atom(), |(kind, text, _)| *kind == NUM && *text == "1");
+        assert_eq!(
+            Ok((&[][..], vec![])),
+            zero_or_more(num_parser).parse(&[][..])
+        );
 
-        let num_parser = judge(atom(), |(kind, text)| *kind == NUM && text == "1");
-        let input = vec![num_one.clone(), num_one.clone(), num_one.clone()];
+        let num_parser = judge(atom(), |(kind, text, _)| *kind == NUM && *text == "1");
+        let input = [num_one, num_one, num_one];
         assert_eq!(
-            Ok((
-                vec![],
-                vec![num_one.clone(), num_one.clone(), num_one.clone()]
-            )),
-            zero_or_more(num_parser).parse(input)
+            Ok((&input[3..], vec![num_one, num_one, num_one])),
+            zero_or_more(num_parser).parse(&input[..])
         );
     }
+
+    #[test]
+    fn zero_or_more_fails_at_partial_match() {
+        let plus_num_parser = and_then(judge(atom(), |(kind, _, _)| *kind == PLUS), |_| {
+            judge(atom(), |(kind, _, _)| *kind == NUM)
+        });
+        let input = [
+            (PLUS, "+", Span::default()),
+            (NUM, "1", Span::default()),
+            (PLUS, "+", Span::default()),
+        ];
+        assert_eq!(
+            Err(ParseError::new(&input[3..])),
+            zero_or_more(plus_num_parser).parse(&input[..])
+        );
+    }
+
+    #[test]
+    fn test_optional() {
+        let num_one = (NUM, "1", Span::default());
+        let plus = (PLUS, "+", Span::default());
+
+        let num_parser = judge(atom(), |(kind, _, _)| *kind == NUM);
+        let input = [num_one];
+        assert_eq!(
+            Ok((&input[1..], Some(num_one))),
+            optional(num_parser).parse(&input[..])
+        );
+
+        let num_parser = judge(atom(), |(kind, _, _)| *kind == NUM);
+        let input = [plus];
+        assert_eq!(
+            Ok((&input[..], None)),
+            optional(num_parser).parse(&input[..])
+        );
+
+        let plus_num_parser = and_then(judge(atom(), |(kind, _, _)| *kind == PLUS), |_| {
+            judge(atom(), |(kind, _, _)| *kind == NUM)
+        });
+        let input = [plus, plus];
+        assert_eq!(
+            Err(ParseError::new(&input[1..])),
+            optional(plus_num_parser).parse(&input[..])
+        );
+    }
+
+    #[test]
+    fn either_keeps_the_farthest_error() {
+        let input = [(NUM, "1", Span::default()), (NUM, "2", Span::default())];
+        let plus_parser = judge(atom(), |(kind, _, _)| *kind == PLUS);
+        let num_plus_parser = and_then(judge(atom(), |(kind, _, _)| *kind == NUM), |_| {
+            judge(atom(), |(kind, _, _)| *kind == PLUS)
+        });
+        assert_eq!(
+            Err(ParseError::new(&input[1..])),
+            either(plus_parser, num_plus_parser).parse(&input[..])
+        );
+    }
+
+    #[test]
+    fn too_deep_is_never_recovered() {
+        let input = [(NUM, "1", Span::default())];
+        fn too_deep(input: Tokens) -> ParserResult<Tokens, Token> {
+            Err(ParseError::too_deep(input))
+        }
+        let expected = Err(ParseError::too_deep(&input[..]));
+        assert_eq!(expected, either(too_deep, atom()).parse(&input[..]));
+        assert_eq!(
+            expected.clone().map(|(rest, _)| (rest, None)),
+            optional(too_deep).parse(&input[..])
+        );
+        assert_eq!(
+            expected.map(|(rest, _)| (rest, vec![])),
+            zero_or_more(too_deep).parse(&input[..])
+        );
+    }
+
+    #[test]
+    fn rest_is_a_subslice_of_the_input() {
+        let input = [
+            (NUM, "1", Span::default()),
+            (PLUS, "+", Span::default()),
+            (NUM, "2", Span::default()),
+        ];
+        let (rest, _) = zero_or_more(atom()).parse(&input[..2]).unwrap();
+        assert!(std::ptr::eq(rest.as_ptr(), input[2..].as_ptr()));
+    }
+
+    #[test]
+    fn test_one_or_more() {
+        let num = (NUM, "1", Span::default());
+        let plus = (PLUS, "+", Span::default());
+
+        let input = [num, num, plus];
+        assert_eq!(
+            Ok((&input[2..], vec![num, num])),
+            one_or_more(judge(atom(), |(kind, _, _)| *kind == NUM)).parse(&input[..])
+        );
+        let input = [plus];
+        assert_eq!(
+            Err(ParseError::new(&input[..])),
+            one_or_more(judge(atom(), |(kind, _, _)| *kind == NUM)).parse(&input[..])
+        );
+    }
+
+    #[test]
+    fn test_sep_by() {
+        let num = (NUM, "1", Span::default());
+        let plus = (PLUS, "+", Span::default());
+        let num_sep_by_plus = || {
+            sep_by(
+                judge(atom(), |(kind, _, _)| *kind == NUM),
+                judge(atom(), |(kind, _, _)| *kind == PLUS),
+            )
+        };
+
+        let input = [num, plus, num, num];
+        assert_eq!(
+            Ok((&input[3..], vec![num, num])),
+            num_sep_by_plus().parse(&input[..])
+        );
+        let input = [plus];
+        assert_eq!(
+            Ok((&input[..], vec![])),
+            num_sep_by_plus().parse(&input[..])
+        );
+        // the trailing separator is a partial match
+        let input = [num, plus];
+        assert_eq!(
+            Err(ParseError::new(&input[2..])),
+            num_sep_by_plus().parse(&input[..])
+        );
+    }
+
+    #[test]
+    fn test_between() {
+        let input = [
+            (PLUS, "+", Span::default()),
+            (NUM, "1", Span::default()),
+            (PLUS, "+", Span::default()),
+        ];
+        let plus = || judge(atom(), |(kind, _, _)| *kind == PLUS);
+        assert_eq!(
+            Ok((&input[3..], (NUM, "1", Span::default()))),
+            between(plus(), atom(), plus()).parse(&input[..])
+        );
+        assert_eq!(
+            Err(ParseError::new(&input[2..])),
+            between(plus(), atom(), eof()).parse(&input[..])
+        );
+    }
+
+    #[test]
+    fn test_chain() {
+        // "1 + 2 + 3", the text of the operands is folded to show the associativity
+        let input = [
+            (NUM, "1", Span::default()),
+            (PLUS, "+", Span::default()),
+            (NUM, "2", Span::default()),
+            (PLUS, "+", Span::default()),
+            (NUM, "3", Span::default()),
+        ];
+        let operand = || {
+            judge(atom::<Tokens>(), |(kind, _, _)| *kind == NUM)
+                .map(|(_, text, _)| text.to_string())
+        };
+        let op = || judge(atom(), |(kind, _, _)| *kind == PLUS);
+        let fold = |left: String, _, right: String| format!("({} + {})", left, right);
+
+        assert_eq!(
+            Ok((&input[5..], "((1 + 2) + 3)".to_string())),
+            chainl1(operand(), op(), fold).parse(&input[..])
+        );
+        assert_eq!(
+            Ok((&input[5..], "(1 + (2 + 3))".to_string())),
+            chainr1(operand(), op(), fold).parse(&input[..])
+        );
+        assert_eq!(
+            Ok((&[][..], "1".to_string())),
+            chainr1(operand(), op(), fold).parse(&input[..1])
+        );
+        assert_eq!(
+            Err(ParseError::new(&[][..])),
+            chainl1(operand(), op(), fold).parse(&input[..4])
+        );
+    }
+
+    #[test]
+    fn test_not_followed_by() {
+        let input = [(NUM, "1", Span::default()), (PLUS, "+", Span::default())];
+        let plus = || judge(atom(), |(kind, _, _)| *kind == PLUS);
+        assert_eq!(
+            Ok((&input[1..], ((NUM, "1", Span::default()), ()))),
+            pair(
+                atom(),
+                not_followed_by(judge(atom(), |(kind, _, _)| *kind == NUM))
+            )
+            .parse(&input[..])
+        );
+        assert_eq!(
+            Err(ParseError::new(&input[1..])),
+            pair(atom(), not_followed_by(plus())).parse(&input[..])
+        );
+    }
+
+    #[test]
+    fn test_lazy() {
+        // Nested -> NUM | PLUS Nested
+        fn nested<'input>() -> impl Parser<Tokens<'input>, usize> {
+            either(
+                judge(atom(), |(kind, _, _)| *kind == NUM).map(|_| 0),
+                pair(judge(atom(), |(kind, _, _)| *kind == PLUS), lazy(nested))
+                    .map(|(_, depth)| depth + 1),
+            )
+        }
+        let input = [
+            (PLUS, "+", Span::default()),
+            (PLUS, "+", Span::default()),
+            (NUM, "1", Span::default()),
+        ];
+        assert_eq!(Ok((&input[3..], 2)), nested().parse(&input[..]));
+    }
 }
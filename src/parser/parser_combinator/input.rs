@@ -0,0 +1,128 @@
+use std::fmt::Debug;
+
+use crate::lexer::Token;
+use crate::syntax_kind::SyntaxKind;
+
+/// the remaining tokens from `lex` to be parsed,
+/// a parser consumes some tokens by returning a shorter slice, without copying the tokens
+pub type Tokens<'input> = &'input [Token<'input>];
+
+/// the input which the parsers consume from the front
+///
+/// it is implemented for:
+/// - `&[T]`, a slice of the tokens which have a kind, e.g. `Tokens`
+/// - `&str`, to parse the characters
+pub trait Input: Copy {
+    /// the smallest unit of the input, e.g. a `Token` or a `char`
+    type Item;
+    /// what `tag` matches, which is reported as expected if it does not match
+    type Tag: Copy + PartialEq + Debug;
+    /// the part of the input matched by `tag`
+    type Matched;
+
+    /// split the first item off, if any
+    fn split_first(self) -> Option<(Self::Item, Self)>;
+    /// split the part matched by the tag off, if it matches
+    fn split_tag(self, tag: Self::Tag) -> Option<(Self::Matched, Self)>;
+    /// the length of the remaining input, a shorter one went farther
+    fn input_len(self) -> usize;
+}
+
+/// a token which has a kind, so that a slice of them can be matched by `tag`
+///
+/// # Example
+/// ```rust
+/// #[derive(Clone, Copy)]
+/// enum Tok { Num(i64), Plus }
+///
+/// impl TokenLike for Tok {
+///     type Kind = &'static str;
+///     fn kind(&self) -> &'static str {
+///         match self {
+///             Tok::Num(_) => "number",
+///             Tok::Plus => "+",
+///         }
+///     }
+/// }
+/// ```
+pub trait TokenLike {
+    type Kind: Copy + PartialEq + Debug;
+
+    fn kind(&self) -> Self::Kind;
+}
+
+impl TokenLike for Token<'_> {
+    type Kind = SyntaxKind;
+
+    fn kind(&self) -> SyntaxKind {
+        self.0
+    }
+}
+
+impl<T: TokenLike + Copy> Input for &[T] {
+    type Item = T;
+    type Tag = T::Kind;
+    type Matched = T;
+
+    fn split_first(self) -> Option<(T, Self)> {
+        <[T]>::split_first(self).map(|(first, rest)| (*first, rest))
+    }
+    /// a token of the kind
+    fn split_tag(self, kind: T::Kind) -> Option<(T, Self)> {
+        Input::split_first(self).filter(|(first, _)| first.kind() == kind)
+    }
+    fn input_len(self) -> usize {
+        self.len()
+    }
+}
+
+impl<'input> Input for &'input str {
+    type Item = char;
+    type Tag = &'static str;
+    type Matched = &'input str;
+
+    fn split_first(self) -> Option<(char, Self)> {
+        let mut chars = self.chars();
+        chars.next().map(|c| (c, chars.as_str()))
+    }
+    /// a prefix of the string
+    fn split_tag(self, tag: &'static str) -> Option<(&'input str, Self)> {
+        self.strip_prefix(tag)
+            .map(|rest| (&self[..tag.len()], rest))
+    }
+    /// in bytes
+    fn input_len(self) -> usize {
+        self.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::span::Span;
+    use crate::syntax_kind::{NUM, PLUS};
+
+    #[test]
+    fn token_slice() {
+        let input: Tokens = &[(NUM, "1", Span::default()), (PLUS, "+", Span::default())];
+        assert_eq!(
+            Some(((NUM, "1", Span::default()), &input[1..])),
+            Input::split_first(input)
+        );
+        assert_eq!(
+            Some(((NUM, "1", Span::default()), &input[1..])),
+            input.split_tag(NUM)
+        );
+        assert_eq!(None, input.split_tag(PLUS));
+        assert_eq!(2, input.input_len());
+    }
+
+    #[test]
+    fn string() {
+        assert_eq!(Some(('α', "+1")), Input::split_first("α+1"));
+        assert_eq!(None, Input::split_first(""));
+        assert_eq!(Some(("**", "2")), "**2".split_tag("**"));
+        assert_eq!(None, "*2".split_tag("**"));
+        assert_eq!(4, "α+1".input_len());
+    }
+}
@@ -1,6 +1,16 @@
 mod basic_parser;
 mod boxed_parser;
 mod combinator;
+mod input;
 mod traits;
 
-pub(crate) use {basic_parser::single_token, combinator::*, traits::Parser};
+pub use {
+    basic_parser::{atom, eof, satisfy, single_token, tag},
+    boxed_parser::BoxedParser,
+    combinator::{
+        and_then, between, chainl1, chainr1, either, judge, lazy, map, not_followed_by,
+        one_or_more, optional, pair, sep_by, zero_or_more,
+    },
+    input::{Input, TokenLike, Tokens},
+    traits::{ParseError, Parser, ParserResult},
+};
@@ -1,44 +1,98 @@
-use crate::lexer::TokenStream;
 use crate::parser::parser_combinator::boxed_parser::BoxedParser;
+use crate::parser::parser_combinator::input::Input;
 use crate::parser::parser_combinator::{and_then, either, map};
 
 /// the result of once parsing
 /// - Ok(Next Input, Current Output)
-/// - Err(Current Input)
-pub type ParserResult<Output> = Result<(TokenStream, Output), TokenStream>;
+/// - Err(ParseError)
+pub type ParserResult<I, Output> = Result<(I, Output), ParseError<I>>;
 
-pub trait Parser<'input, Output> {
-    fn parse(&self, input: TokenStream) -> ParserResult<Output>;
+/// the reason why a parsing failed
+///
+/// input: the remaining input at the point of failure, whose first item is the offending one
+/// expected: the tags which would have been accepted there, e.g. the token kinds
+/// too_deep: the parsing gave up since the input is nested too deeply, rather than overflowing the stack,
+/// such an error is never recovered from by trying another alternative
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError<I: Input> {
+    pub input: I,
+    pub expected: Vec<I::Tag>,
+    pub too_deep: bool,
+}
+
+impl<I: Input> ParseError<I> {
+    pub fn new(input: I) -> Self {
+        ParseError {
+            input,
+            expected: Vec::new(),
+            too_deep: false,
+        }
+    }
+    /// give up at the input, which is nested too deeply
+    pub fn too_deep(input: I) -> Self {
+        ParseError {
+            too_deep: true,
+            ..ParseError::new(input)
+        }
+    }
+    pub fn expecting(mut self, tag: I::Tag) -> Self {
+        if !self.expected.contains(&tag) {
+            self.expected.push(tag);
+        }
+        self
+    }
+    /// keep the error which went farther, or combine the expectations if both stopped at the same item
+    pub fn merge(self, other: ParseError<I>) -> ParseError<I> {
+        match self.input.input_len().cmp(&other.input.input_len()) {
+            std::cmp::Ordering::Less => self,
+            std::cmp::Ordering::Greater => other,
+            std::cmp::Ordering::Equal => {
+                let too_deep = self.too_deep || other.too_deep;
+                let merged = other
+                    .expected
+                    .into_iter()
+                    .fold(self, |err, tag| err.expecting(tag));
+                ParseError { too_deep, ..merged }
+            }
+        }
+    }
+}
 
-    fn map<MapFn, NewOutput>(self, map_fn: MapFn) -> BoxedParser<'input, NewOutput>
+pub trait Parser<I: Input, Output> {
+    fn parse(&self, input: I) -> ParserResult<I, Output>;
+
+    fn map<'a, MapFn, NewOutput>(self, map_fn: MapFn) -> BoxedParser<'a, I, NewOutput>
     where
-        Self: Sized + 'input,
-        Output: 'input,
-        NewOutput: 'input,
-        MapFn: Fn(Output) -> NewOutput + 'input,
+        Self: Sized + 'a,
+        I: 'a,
+        Output: 'a,
+        NewOutput: 'a,
+        MapFn: Fn(Output) -> NewOutput + 'a,
     {
         BoxedParser::new(map(self, map_fn))
     }
 
-    fn and_then<NextFn, NextParser, NextOutput>(
+    fn and_then<'a, NextFn, NextParser, NextOutput>(
         self,
         next_fn: NextFn,
-    ) -> BoxedParser<'input, NextOutput>
+    ) -> BoxedParser<'a, I, NextOutput>
     where
-        Self: Sized + 'input,
-        Output: 'input,
-        NextParser: Parser<'input, NextOutput> + 'input,
-        NextFn: Fn(Output) -> NextParser + 'input,
-        NextOutput: 'input,
+        Self: Sized + 'a,
+        I: 'a,
+        Output: 'a,
+        NextParser: Parser<I, NextOutput> + 'a,
+        NextFn: Fn(Output) -> NextParser + 'a,
+        NextOutput: 'a,
     {
         BoxedParser::new(and_then(self, next_fn))
     }
 
-    fn or<OtherParser>(self, other_parser: OtherParser) -> BoxedParser<'input, Output>
+    fn or<'a, OtherParser>(self, other_parser: OtherParser) -> BoxedParser<'a, I, Output>
     where
-        Self: Sized + 'input,
-        Output: 'input,
-        OtherParser: Parser<'input, Output> + 'input,
+        Self: Sized + 'a,
+        I: 'a,
+        Output: 'a,
+        OtherParser: Parser<I, Output> + 'a,
     {
         BoxedParser::new(either(self, other_parser))
     }
@@ -48,22 +102,23 @@ pub trait Parser<'input, Output> {
 ///
 /// `Parser-Like` function:
 ///
-///     Fn(TokenStream) -> ParserResult<Output>
+///     Fn(I) -> ParserResult<I, Output>
 ///
 /// # Example
 ///
 /// ```rust
-/// fn get_a_parser_like_function() -> impl Parser<i32> {
-///     |input: TokenStream| {
+/// fn get_a_parser_like_function<'input>() -> impl Parser<&'input str, i32> {
+///     |input: &'input str| {
 ///         Ok((input, 666))
 ///     }
 /// }
 /// ```
-impl<'input, Output, F> Parser<'input, Output> for F
+impl<I, Output, F> Parser<I, Output> for F
 where
-    F: Fn(TokenStream) -> ParserResult<Output>,
+    I: Input,
+    F: Fn(I) -> ParserResult<I, Output>,
 {
-    fn parse(&self, input: TokenStream) -> ParserResult<Output> {
+    fn parse(&self, input: I) -> ParserResult<I, Output> {
         self(input)
     }
 }
@@ -72,32 +127,32 @@ where
 mod tests {
     use crate::parser::parser_combinator::basic_parser::atom;
     use crate::parser::parser_combinator::{judge, Parser};
+    use crate::span::Span;
     use crate::syntax_kind::{NUM, PLUS};
 
     #[test]
     fn test_chained_call() {
-        let input = vec![(NUM, "1".to_string())];
+        let input = [(NUM, "1", Span::default())];
         assert_eq!(
-            Ok((vec![], (PLUS, "+".to_string()))),
-            atom().map(|_| (PLUS, "+".to_string())).parse(input)
+            Ok((&input[1..], (PLUS, "+", Span::default()))),
+            atom()
+                .map(|_| (PLUS, "+", Span::default()))
+                .parse(&input[..])
         );
     }
 
     #[test]
     fn att() {
-        let input = vec![
-            (NUM, "1".to_string()),
-            (PLUS, "+".to_string()),
-            (NUM, "2".to_string()),
+        let input = [
+            (NUM, "1", Span::default()),
+            (PLUS, "+", Span::default()),
+            (NUM, "2", Span::default()),
         ];
         assert_eq!(
-            Ok((
-                vec![(PLUS, "+".to_string()), (NUM, "2".to_string())],
-                (PLUS, "1".to_string())
-            )),
-            judge(atom(), |(kind, _)| *kind == NUM)
-                .map(|(_, text)| (PLUS, text))
-                .parse(input)
-        )
+            Ok((&input[1..], (PLUS, "1", Span::default()))),
+            judge(atom(), |(kind, _, _)| *kind == NUM)
+                .map(|(_, text, _)| (PLUS, text, Span::default()))
+                .parse(&input[..])
+        );
     }
 }
@@ -0,0 +1,308 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::bigint::BigInt;
+
+/// the largest power of ten in a literal, which keeps it within `BigInt::MAX_BITS` bits
+const MAX_EXP10: u64 = BigInt::MAX_BITS * 3 / 10;
+
+/// exact fraction `numer / denom`
+///
+/// it is always reduced, and the sign is carried by the numerator, so that `denom > 0`
+///
+/// # Example
+/// ```rust
+/// let ast = build_ast("1 / 3 * 3 + 0.25").unwrap();
+/// let value = eval_as::<Rational>(&ast, EvalOptions::default()).unwrap();
+/// assert_eq!("5/4", value.to_string());
+/// assert_eq!("1.25", value.to_decimal(2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: BigInt,
+    denom: BigInt,
+}
+
+impl Rational {
+    /// build a reduced fraction, or `None` if `denom` is zero
+    pub fn new(numer: BigInt, denom: BigInt) -> Option<Self> {
+        if denom.is_zero() {
+            return None;
+        }
+        let divisor = gcd(numer.clone(), denom.clone());
+        // the divisor is never zero since `denom` is not
+        let mut numer = numer.checked_div(&divisor).unwrap();
+        let mut denom = denom.checked_div(&divisor).unwrap();
+        if denom.is_negative() {
+            numer = -numer;
+            denom = -denom;
+        }
+        Some(Rational { numer, denom })
+    }
+    pub fn numer(&self) -> &BigInt {
+        &self.numer
+    }
+    pub fn denom(&self) -> &BigInt {
+        &self.denom
+    }
+    pub fn is_zero(&self) -> bool {
+        self.numer.is_zero()
+    }
+    pub fn is_integer(&self) -> bool {
+        self.denom == BigInt::from(1)
+    }
+
+    /// the integer part, rounding toward zero, e.g. `-7/2` is `-3`
+    pub fn trunc(&self) -> BigInt {
+        self.numer.checked_div(&self.denom).unwrap()
+    }
+
+    /// the quotient, or `None` if `rhs` is zero
+    pub fn checked_div(self, rhs: Rational) -> Option<Rational> {
+        Rational::new(self.numer * rhs.denom, self.denom * rhs.numer)
+    }
+
+    /// parse a number literal exactly, e.g. `42`, `-1.25`, `2.5e-3`
+    ///
+    /// return `None` if the power of ten is beyond `MAX_EXP10`, e.g. `1e100000000`
+    pub fn from_literal(raw: &str) -> Option<Self> {
+        let (mantissa, exp) = match raw.find(['e', 'E']) {
+            Some(idx) => (&raw[..idx], raw[idx + 1..].parse::<i64>().ok()?),
+            None => (raw, 0),
+        };
+        let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if !frac_part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        // `1.25e1` is `125 / 10^2 * 10^1`
+        let numer: BigInt = format!("{}{}", int_part, frac_part).parse().ok()?;
+        let exp = exp.checked_sub(frac_part.len() as i64)?;
+        if exp.unsigned_abs() > MAX_EXP10 {
+            return None;
+        }
+        let scale = pow10(exp.unsigned_abs());
+        match exp < 0 {
+            true => Rational::new(numer, scale),
+            false => Rational::new(numer * scale, BigInt::from(1)),
+        }
+    }
+
+    /// render as a decimal with `precision` digits after the point,
+    /// rounding half away from zero
+    ///
+    /// # Example
+    /// ```rust
+    /// let value = Rational::new(BigInt::from(-2), BigInt::from(3)).unwrap();
+    /// assert_eq!("-0.667", value.to_decimal(3));
+    /// assert_eq!("-1", value.to_decimal(0));
+    /// ```
+    pub fn to_decimal(&self, precision: usize) -> String {
+        let scale = pow10(precision as u64);
+        // round(|numer| * scale / denom) = (2 * |numer| * scale + denom) / (2 * denom)
+        let two = BigInt::from(2);
+        let scaled = (two.clone() * self.numer.clone().abs() * scale.clone() + self.denom.clone())
+            .checked_div(&(two * self.denom.clone()))
+            .unwrap();
+
+        let (int_part, frac_part) = scaled.checked_div_rem(&scale).unwrap();
+        let mut output = String::new();
+        if self.numer.is_negative() && !scaled.is_zero() {
+            output.push('-');
+        }
+        output.push_str(&int_part.to_string());
+        if precision > 0 {
+            output.push('.');
+            output.push_str(&format!(
+                "{:0>width$}",
+                frac_part.to_string(),
+                width = precision
+            ));
+        }
+        output
+    }
+}
+
+impl From<BigInt> for Rational {
+    fn from(value: BigInt) -> Self {
+        Rational {
+            numer: value,
+            denom: BigInt::from(1),
+        }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Rational::from(BigInt::from(value))
+    }
+}
+
+/// render as `p/q`, or just `p` for an integer
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.is_integer() {
+            true => write!(f, "{}", self.numer),
+            false => write!(f, "{}/{}", self.numer, self.denom),
+        }
+    }
+}
+
+impl Ord for Rational {
+    /// `a/b < c/d` is `a*d < c*b` since both denominators are positive
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numer.clone() * other.denom.clone()).cmp(&(other.numer.clone() * self.denom.clone()))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Rational) -> Rational {
+        Rational::new(
+            self.numer * rhs.denom.clone() + rhs.numer * self.denom.clone(),
+            self.denom * rhs.denom,
+        )
+        .unwrap()
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Rational) -> Rational {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Rational) -> Rational {
+        Rational::new(self.numer * rhs.numer, self.denom * rhs.denom).unwrap()
+    }
+}
+
+/// the greatest common divisor, which is not negative
+fn gcd(mut a: BigInt, mut b: BigInt) -> BigInt {
+    while !b.is_zero() {
+        let r = a.checked_rem(&b).unwrap();
+        a = b;
+        b = r;
+    }
+    a.abs()
+}
+
+/// `10^exp` by squaring
+fn pow10(mut exp: u64) -> BigInt {
+    let (mut base, mut acc) = (BigInt::from(10), BigInt::from(1));
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = acc * base.clone();
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = base.clone() * base;
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ratio(numer: i64, denom: i64) -> Rational {
+        Rational::new(BigInt::from(numer), BigInt::from(denom)).unwrap()
+    }
+
+    #[test]
+    fn reduce() {
+        assert_eq!("2/3", ratio(4, 6).to_string());
+        assert_eq!("-2/3", ratio(4, -6).to_string());
+        assert_eq!("2/3", ratio(-4, -6).to_string());
+        assert_eq!("0", ratio(0, -6).to_string());
+        assert_eq!("5", ratio(10, 2).to_string());
+        assert_eq!(None, Rational::new(BigInt::from(1), BigInt::from(0)));
+    }
+
+    #[test]
+    fn from_literal() {
+        assert_eq!(Some(ratio(42, 1)), Rational::from_literal("42"));
+        assert_eq!(Some(ratio(-5, 4)), Rational::from_literal("-1.25"));
+        assert_eq!(Some(ratio(1, 400)), Rational::from_literal("2.5e-3"));
+        assert_eq!(Some(ratio(1500, 1)), Rational::from_literal("1.5E3"));
+        assert_eq!(Some(ratio(1, 10)), Rational::from_literal("0.1"));
+        assert_eq!(None, Rational::from_literal("1.x"));
+    }
+
+    #[test]
+    fn from_literal_limits_the_exponent() {
+        let max = format!("1e{}", MAX_EXP10);
+        assert_eq!(
+            Some(Rational::from(pow10(MAX_EXP10))),
+            Rational::from_literal(&max)
+        );
+        assert_eq!(
+            None,
+            Rational::from_literal(&format!("1e{}", MAX_EXP10 + 1))
+        );
+        assert_eq!(None, Rational::from_literal("1e100000000"));
+        assert_eq!(None, Rational::from_literal("1e-100000000"));
+        assert_eq!(BigInt::from(1_000_000_000_000_i64), pow10(12));
+        assert_eq!(BigInt::from(1), pow10(0));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(ratio(5, 6), ratio(1, 2) + ratio(1, 3));
+        assert_eq!(ratio(1, 6), ratio(1, 2) - ratio(1, 3));
+        assert_eq!(ratio(-1, 6), ratio(1, 3) - ratio(1, 2));
+        assert_eq!(ratio(1, 1), ratio(1, 3) * ratio(3, 1));
+        assert_eq!(Some(ratio(-3, 2)), ratio(1, 2).checked_div(ratio(-1, 3)));
+        assert_eq!(None, ratio(1, 2).checked_div(ratio(0, 1)));
+    }
+
+    #[test]
+    fn trunc() {
+        assert_eq!(BigInt::from(3), ratio(7, 2).trunc());
+        assert_eq!(BigInt::from(-3), ratio(-7, 2).trunc());
+        assert_eq!(BigInt::from(0), ratio(-1, 2).trunc());
+    }
+
+    #[test]
+    fn compare() {
+        assert!(ratio(1, 3) < ratio(1, 2));
+        assert!(ratio(-1, 2) < ratio(-1, 3));
+        assert_eq!(Ordering::Equal, ratio(2, 4).cmp(&ratio(1, 2)));
+    }
+
+    #[test]
+    fn to_decimal() {
+        assert_eq!("0.333", ratio(1, 3).to_decimal(3));
+        assert_eq!("0.667", ratio(2, 3).to_decimal(3));
+        assert_eq!("-0.667", ratio(-2, 3).to_decimal(3));
+        assert_eq!("2.50", ratio(5, 2).to_decimal(2));
+        assert_eq!("3", ratio(5, 2).to_decimal(0));
+        assert_eq!("-3", ratio(-5, 2).to_decimal(0));
+        assert_eq!("0.00", ratio(-1, 1000).to_decimal(2));
+        assert_eq!("0.05", ratio(1, 20).to_decimal(2));
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+
+/// a byte range `[start, end)` of the source
+#[derive(Debug, Default, Clone, Copy, PartialOrd, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+    /// the smallest span which covers both `self` and `other`
+    pub fn merge(self, other: Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+    /// get the text covered by the span
+    ///
+    /// return `None` if the span is out of the source, or does not fall on the character boundaries,
+    /// e.g. it comes from another source
+    pub fn slice(self, src: &str) -> Option<&str> {
+        src.get(self.start..self.end)
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// convert a byte offset into a 1-based (line, column) pair
+///
+/// the column is counted in characters, and an offset beyond the source points right after its end,
+/// an offset inside a multi-byte character points at that character
+///
+/// # Example
+/// ```rust
+/// assert_eq!((2, 3), line_col("1 +\n2 * 3", 6));
+/// ```
+pub fn line_col(src: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(src.len());
+    while !src.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &src[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        assert_eq!(Span::new(0, 5), Span::new(0, 1).merge(Span::new(4, 5)));
+        assert_eq!(Span::new(0, 5), Span::new(4, 5).merge(Span::new(0, 1)));
+    }
+
+    #[test]
+    fn test_slice() {
+        assert_eq!(Some("2 * 3"), Span::new(4, 9).slice("1 + 2 * 3"));
+        assert_eq!(Some(""), Span::new(9, 9).slice("1 + 2 * 3"));
+        assert_eq!(None, Span::new(4, 10).slice("1 + 2 * 3"));
+        assert_eq!(None, Span::new(5, 4).slice("1 + 2 * 3"));
+        // inside `β`, which is the bytes 2..4
+        assert_eq!(None, Span::new(0, 3).slice("αβ+1"));
+    }
+
+    #[test]
+    fn test_line_col() {
+        let src = "1 +\n2 * 3\n";
+        assert_eq!((1, 1), line_col(src, 0));
+        assert_eq!((1, 3), line_col(src, 2));
+        assert_eq!((2, 1), line_col(src, 4));
+        assert_eq!((2, 3), line_col(src, 6));
+        assert_eq!((3, 1), line_col(src, src.len()));
+        assert_eq!((3, 1), line_col(src, 100));
+    }
+
+    #[test]
+    fn crlf_is_one_line_break() {
+        let src = "1 +\r\n2 * 3\r\n";
+        assert_eq!((1, 4), line_col(src, 3));
+        assert_eq!((2, 1), line_col(src, 5));
+        assert_eq!((2, 3), line_col(src, 7));
+        assert_eq!((3, 1), line_col(src, src.len()));
+    }
+
+    #[test]
+    fn column_counts_characters() {
+        assert_eq!((1, 3), line_col("αβ+1", 4));
+        // inside `β`, which is the bytes 2..4
+        assert_eq!((1, 2), line_col("αβ+1", 3));
+        assert_eq!((2, 1), line_col("α\n😀", 4));
+        assert_eq!((2, 1), line_col("α\n😀", 6));
+    }
+}
@@ -7,6 +7,29 @@ pub const ADD_EXPR: SyntaxKind = SyntaxKind(6);
 pub const SUB_EXPR: SyntaxKind = SyntaxKind(7);
 pub const MUL_EXPR: SyntaxKind = SyntaxKind(8);
 pub const DIV_EXPR: SyntaxKind = SyntaxKind(9);
+pub const IDENT: SyntaxKind = SyntaxKind(10);
+pub const CALL_EXPR: SyntaxKind = SyntaxKind(11);
+pub const NEG_EXPR: SyntaxKind = SyntaxKind(12);
+pub const POS_EXPR: SyntaxKind = SyntaxKind(13);
+pub const POW_EXPR: SyntaxKind = SyntaxKind(14);
+pub const REM_EXPR: SyntaxKind = SyntaxKind(15);
+pub const INT_DIV_EXPR: SyntaxKind = SyntaxKind(16);
+pub const EQ_EXPR: SyntaxKind = SyntaxKind(17);
+pub const NE_EXPR: SyntaxKind = SyntaxKind(18);
+pub const LT_EXPR: SyntaxKind = SyntaxKind(19);
+pub const LE_EXPR: SyntaxKind = SyntaxKind(20);
+pub const GT_EXPR: SyntaxKind = SyntaxKind(21);
+pub const GE_EXPR: SyntaxKind = SyntaxKind(22);
+pub const AND_EXPR: SyntaxKind = SyntaxKind(23);
+pub const OR_EXPR: SyntaxKind = SyntaxKind(24);
+pub const NOT_EXPR: SyntaxKind = SyntaxKind(25);
+pub const COND_EXPR: SyntaxKind = SyntaxKind(26);
+pub const BIT_AND_EXPR: SyntaxKind = SyntaxKind(27);
+pub const BIT_OR_EXPR: SyntaxKind = SyntaxKind(28);
+pub const BIT_XOR_EXPR: SyntaxKind = SyntaxKind(29);
+pub const SHL_EXPR: SyntaxKind = SyntaxKind(30);
+pub const SHR_EXPR: SyntaxKind = SyntaxKind(31);
+pub const BIT_NOT_EXPR: SyntaxKind = SyntaxKind(32);
 
 // token
 pub const OPEN_PAREN: SyntaxKind = SyntaxKind(100);
@@ -15,10 +38,40 @@ pub const PLUS: SyntaxKind = SyntaxKind(102);
 pub const MINUS: SyntaxKind = SyntaxKind(103);
 pub const STAR: SyntaxKind = SyntaxKind(104);
 pub const SLASH: SyntaxKind = SyntaxKind(105);
+pub const COMMA: SyntaxKind = SyntaxKind(106);
+pub const DOUBLE_STAR: SyntaxKind = SyntaxKind(107);
+pub const PERCENT: SyntaxKind = SyntaxKind(108);
+pub const DOUBLE_SLASH: SyntaxKind = SyntaxKind(109);
+pub const EQ_EQ: SyntaxKind = SyntaxKind(110);
+pub const BANG_EQ: SyntaxKind = SyntaxKind(111);
+pub const LT: SyntaxKind = SyntaxKind(112);
+pub const LT_EQ: SyntaxKind = SyntaxKind(113);
+pub const GT: SyntaxKind = SyntaxKind(114);
+pub const GT_EQ: SyntaxKind = SyntaxKind(115);
+pub const AMP_AMP: SyntaxKind = SyntaxKind(116);
+pub const PIPE_PIPE: SyntaxKind = SyntaxKind(117);
+pub const BANG: SyntaxKind = SyntaxKind(118);
+pub const QUESTION: SyntaxKind = SyntaxKind(119);
+pub const COLON: SyntaxKind = SyntaxKind(120);
+pub const AMP: SyntaxKind = SyntaxKind(121);
+pub const PIPE: SyntaxKind = SyntaxKind(122);
+pub const CARET: SyntaxKind = SyntaxKind(123);
+pub const TILDE: SyntaxKind = SyntaxKind(124);
+pub const LT_LT: SyntaxKind = SyntaxKind(125);
+pub const GT_GT: SyntaxKind = SyntaxKind(126);
 
 // other
 pub const UNKNOW: SyntaxKind = SyntaxKind(65534);
 
+/// the associativity of a binary operator
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Assoc {
+    /// `a op b op c` is `(a op b) op c`
+    Left,
+    /// `a op b op c` is `a op (b op c)`
+    Right,
+}
+
 impl SyntaxKind {
     /// try to get a operator kind(`SyntaxKind`) from a string reference(`&str`)
     pub fn from_operator(str: &str) -> Option<SyntaxKind> {
@@ -29,6 +82,27 @@ impl SyntaxKind {
             "-" => MINUS,
             "*" => STAR,
             "/" => SLASH,
+            "," => COMMA,
+            "**" => DOUBLE_STAR,
+            "%" => PERCENT,
+            "//" => DOUBLE_SLASH,
+            "==" => EQ_EQ,
+            "!=" => BANG_EQ,
+            "<" => LT,
+            "<=" => LT_EQ,
+            ">" => GT,
+            ">=" => GT_EQ,
+            "&&" => AMP_AMP,
+            "||" => PIPE_PIPE,
+            "!" => BANG,
+            "?" => QUESTION,
+            ":" => COLON,
+            "&" => AMP,
+            "|" => PIPE,
+            "^" => CARET,
+            "~" => TILDE,
+            "<<" => LT_LT,
+            ">>" => GT_GT,
             _ => return None,
         };
         Some(op)
@@ -36,11 +110,27 @@ impl SyntaxKind {
     /// get the priority of the operator, and the higner priority will get a bigger value
     pub fn get_op_priority(str: &str) -> usize {
         match str {
-            "*" | "/" => 2,
-            "+" | "-" => 1,
+            "**" => 11,
+            "*" | "/" | "//" | "%" => 10,
+            "+" | "-" => 9,
+            "<<" | ">>" => 8,
+            "<" | "<=" | ">" | ">=" => 7,
+            "==" | "!=" => 6,
+            "&" => 5,
+            "^" => 4,
+            "|" => 3,
+            "&&" => 2,
+            "||" => 1,
             _ => usize::MAX,
         }
     }
+    /// get the associativity of the operator, only the power is right associative
+    pub fn get_op_assoc(str: &str) -> Assoc {
+        match str {
+            "**" => Assoc::Right,
+            _ => Assoc::Left,
+        }
+    }
     /// tranform a kind value(`SyntaxKind`) into a string reference(`&str`)
     pub fn into_str<'a>(self) -> &'a str {
         match self {
@@ -50,6 +140,29 @@ impl SyntaxKind {
             MINUS => "-",
             STAR => "*",
             SLASH => "/",
+            COMMA => ",",
+            DOUBLE_STAR => "**",
+            PERCENT => "%",
+            DOUBLE_SLASH => "//",
+            EQ_EQ => "==",
+            BANG_EQ => "!=",
+            LT => "<",
+            LT_EQ => "<=",
+            GT => ">",
+            GT_EQ => ">=",
+            AMP_AMP => "&&",
+            PIPE_PIPE => "||",
+            BANG => "!",
+            QUESTION => "?",
+            COLON => ":",
+            AMP => "&",
+            PIPE => "|",
+            CARET => "^",
+            TILDE => "~",
+            LT_LT => "<<",
+            GT_GT => ">>",
+            NUM => "number",
+            IDENT => "identifier",
             _ => "unknow",
         }
     }
@@ -63,6 +176,27 @@ macro_rules! token {
     ["-"] => { $crate::syntax_kind::MINUS };
     ["*"] => { $crate::syntax_kind::STAR };
     ["/"] => { $crate::syntax_kind::SLASH };
+    [","] => { $crate::syntax_kind::COMMA };
+    ["**"] => { $crate::syntax_kind::DOUBLE_STAR };
+    ["%"] => { $crate::syntax_kind::PERCENT };
+    ["//"] => { $crate::syntax_kind::DOUBLE_SLASH };
+    ["=="] => { $crate::syntax_kind::EQ_EQ };
+    ["!="] => { $crate::syntax_kind::BANG_EQ };
+    ["<"] => { $crate::syntax_kind::LT };
+    ["<="] => { $crate::syntax_kind::LT_EQ };
+    [">"] => { $crate::syntax_kind::GT };
+    [">="] => { $crate::syntax_kind::GT_EQ };
+    ["&&"] => { $crate::syntax_kind::AMP_AMP };
+    ["||"] => { $crate::syntax_kind::PIPE_PIPE };
+    ["!"] => { $crate::syntax_kind::BANG };
+    ["?"] => { $crate::syntax_kind::QUESTION };
+    [":"] => { $crate::syntax_kind::COLON };
+    ["&"] => { $crate::syntax_kind::AMP };
+    ["|"] => { $crate::syntax_kind::PIPE };
+    ["^"] => { $crate::syntax_kind::CARET };
+    ["~"] => { $crate::syntax_kind::TILDE };
+    ["<<"] => { $crate::syntax_kind::LT_LT };
+    [">>"] => { $crate::syntax_kind::GT_GT };
 }
 
 #[cfg(test)]
@@ -77,6 +211,20 @@ mod tests {
         assert!(SyntaxKind::from_operator("-").is_some());
         assert!(SyntaxKind::from_operator("*").is_some());
         assert!(SyntaxKind::from_operator("/").is_some());
+        assert!(SyntaxKind::from_operator(",").is_some());
+        assert!(SyntaxKind::from_operator("**").is_some());
+        assert!(SyntaxKind::from_operator("%").is_some());
+        assert!(SyntaxKind::from_operator("//").is_some());
+        for op in [
+            "==", "!=", "<", "<=", ">", ">=", "&&", "||", "!", "?", ":", "&", "|", "^", "~", "<<",
+            ">>",
+        ] {
+            assert_eq!(
+                Some(op),
+                SyntaxKind::from_operator(op).map(|kind| kind.into_str())
+            );
+        }
+        assert!(SyntaxKind::from_operator("=").is_none());
     }
 
     #[test]
@@ -87,5 +235,15 @@ mod tests {
         assert_eq!(token!["-"], MINUS);
         assert_eq!(token!["*"], STAR);
         assert_eq!(token!["/"], SLASH);
+        assert_eq!(token![","], COMMA);
+        assert_eq!(token!["**"], DOUBLE_STAR);
+        assert_eq!(token!["%"], PERCENT);
+        assert_eq!(token!["//"], DOUBLE_SLASH);
+        assert_eq!(token!["<="], LT_EQ);
+        assert_eq!(token!["!"], BANG);
+        assert_eq!(token!["?"], QUESTION);
+        assert_eq!(token![":"], COLON);
+        assert_eq!(token!["&"], AMP);
+        assert_eq!(token![">>"], GT_GT);
     }
 }
@@ -0,0 +1,53 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// supply the values of the variables in a expression
+///
+/// it is implemented for the maps keyed by name and for the closures,
+/// return `None` for an unknown name to report an `EvalErrorKind::UnboundVariable`
+///
+/// # Example
+/// ```rust
+/// let ast = build_ast("price * qty + shipping").unwrap();
+/// let env = HashMap::from([("price", 25), ("qty", 4), ("shipping", 10)]);
+/// assert_eq!(Ok(110), eval_with_env(&ast, &env, EvalOptions::default()));
+/// ```
+pub trait Resolver<N> {
+    fn resolve(&self, name: &str) -> Option<N>;
+}
+
+/// no variable is defined at all
+impl<N> Resolver<N> for () {
+    fn resolve(&self, _: &str) -> Option<N> {
+        None
+    }
+}
+
+impl<N: Clone> Resolver<N> for HashMap<String, N> {
+    fn resolve(&self, name: &str) -> Option<N> {
+        self.get(name).cloned()
+    }
+}
+
+impl<N: Clone> Resolver<N> for HashMap<&str, N> {
+    fn resolve(&self, name: &str) -> Option<N> {
+        self.get(name).cloned()
+    }
+}
+
+impl<N: Clone> Resolver<N> for BTreeMap<String, N> {
+    fn resolve(&self, name: &str) -> Option<N> {
+        self.get(name).cloned()
+    }
+}
+
+impl<N: Clone> Resolver<N> for BTreeMap<&str, N> {
+    fn resolve(&self, name: &str) -> Option<N> {
+        self.get(name).cloned()
+    }
+}
+
+impl<N, F: Fn(&str) -> Option<N>> Resolver<N> for F {
+    fn resolve(&self, name: &str) -> Option<N> {
+        self(name)
+    }
+}
@@ -1,21 +1,217 @@
-use crate::parser::Node;
+use std::marker::PhantomData;
+
+use crate::bigint::BigInt;
+use crate::error::{EvalError, EvalErrorKind};
+use crate::parser::{split_radix, Node, Num};
+use crate::span::Span;
+use crate::traversal::env::Resolver;
+use crate::traversal::function::Registry;
+use crate::traversal::number::Number;
+use crate::traversal::value::{Value, ValueType};
 use crate::traversal::visitor::Visitor;
 
-pub struct Executor;
+/// how to deal with the result of an operation which does not fit in the integer type
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowMode {
+    /// report an `EvalErrorKind::Overflow` or `EvalErrorKind::DivisionOverflow`
+    #[default]
+    Checked,
+    /// wrap around at the boundary of the type, e.g. `i32::MAX + 1 == i32::MIN`
+    ///
+    /// a radix literal which fits in the width of the type is taken as its two's complement,
+    /// e.g. `0xFFFF_FFFF` is `-1` for `i32`, the other modes reject it as an `EvalErrorKind::InvalidLiteral`
+    Wrapping,
+    /// clamp to the boundary of the type, e.g. `i32::MAX + 1 == i32::MAX`
+    Saturating,
+}
+
+/// how to round the quotient of `//` and choose the sign of the remainder of `%`,
+/// which always satisfy `a == (a // b) * b + a % b`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DivisionMode {
+    /// round toward zero, the remainder takes the sign of the dividend, e.g. `-7 // 2 == -3`, `-7 % 2 == -1`
+    Truncated,
+    /// round toward negative infinity, the remainder takes the sign of the divisor,
+    /// e.g. `-7 // 2 == -4`, `-7 % 2 == 1`, `7 % -2 == -1`
+    #[default]
+    Floored,
+    /// the remainder is never negative, e.g. `-7 // 2 == -4`, `-7 % 2 == 1`, `7 % -2 == 1`
+    Euclidean,
+}
+
+/// options to customize the evaluation
+///
+/// # Example
+/// ```rust
+/// let options = EvalOptions {
+///     overflow: OverflowMode::Wrapping,
+///     ..EvalOptions::default()
+/// };
+/// assert_eq!(Ok(i32::MIN), eval_with(&build_ast("2147483647 + 1").unwrap(), options));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EvalOptions {
+    pub overflow: OverflowMode,
+    /// only for `//` and `%`, the `/` is always the division of the numeric type
+    pub division: DivisionMode,
+}
+
+/// evaluate with the numeric type `N`, looking the variables up in `env`
+/// and the functions up in `functions`
+pub struct Executor<'env, N> {
+    options: EvalOptions,
+    env: &'env dyn Resolver<N>,
+    functions: Option<&'env Registry<N>>,
+    number: PhantomData<N>,
+}
 
-impl Executor {
+impl<'env, N: Number> Executor<'env, N> {
     pub fn new() -> Self {
-        Executor
+        Executor::with_options(EvalOptions::default())
     }
-    pub fn eval(&mut self, node: &Node) -> i32 {
+    /// no variable is defined
+    pub fn with_options(options: EvalOptions) -> Self {
+        Executor::with_env(&(), options)
+    }
+    /// no function is defined
+    pub fn with_env(env: &'env dyn Resolver<N>, options: EvalOptions) -> Self {
+        Executor {
+            options,
+            env,
+            functions: None,
+            number: PhantomData,
+        }
+    }
+    pub fn with_registry(
+        env: &'env dyn Resolver<N>,
+        functions: &'env Registry<N>,
+        options: EvalOptions,
+    ) -> Self {
+        Executor {
+            functions: Some(functions),
+            ..Executor::with_env(env, options)
+        }
+    }
+    /// the result must be a number, e.g. `1 < 2` is a `EvalErrorKind::TypeMismatch`
+    pub fn eval(&mut self, node: &Node) -> Result<N, EvalError> {
+        self.number(node)
+    }
+    /// the result is a number or a boolean
+    pub fn eval_value(&mut self, node: &Node) -> Result<Value<N>, EvalError> {
         self.visit(node)
     }
+
+    /// evaluate a operand which must be a number
+    fn number(&mut self, node: &Node) -> Result<N, EvalError> {
+        match self.visit(node)? {
+            Value::Num(value) => Ok(value),
+            value => Err(type_mismatch(ValueType::Number, &value, node.span())),
+        }
+    }
+
+    /// evaluate a operand which must be a boolean
+    fn boolean(&mut self, node: &Node) -> Result<bool, EvalError> {
+        match self.visit(node)? {
+            Value::Bool(value) => Ok(value),
+            value => Err(type_mismatch(ValueType::Bool, &value, node.span())),
+        }
+    }
+}
+
+/// convert the value of a literal, or of its negation, which keeps the exact value for any numeric type
+///
+/// the integer one is parsed in its radix already, and the float one is parsed from the decimal text,
+/// so that e.g. `0.1` is exactly `1/10` for `Rational`
+fn literal<N: Number>(value: &Num, raw: &str, negative: bool, overflow: OverflowMode) -> Option<N> {
+    match (value, negative) {
+        (Num::Int(int), false) => N::from_int(int).or_else(|| bits(int, raw, overflow)),
+        (Num::Int(int), true) => {
+            N::from_int(&-int.clone()).or_else(|| bits::<N>(int, raw, overflow)?.neg(overflow).ok())
+        }
+        (Num::Float(_), false) => N::from_literal(&raw.replace('_', "")),
+        (Num::Float(_), true) => N::from_literal(&format!("-{}", raw.replace('_', ""))),
+    }
+}
+
+/// the two's complement of a radix literal which does not fit as a value, see `OverflowMode::Wrapping`
+fn bits<N: Number>(int: &BigInt, raw: &str, overflow: OverflowMode) -> Option<N> {
+    match (overflow, split_radix(raw)) {
+        (OverflowMode::Wrapping, (radix, _)) if radix != 10 => N::from_twos_complement(int),
+        _ => None,
+    }
+}
+
+fn type_mismatch<N>(expected: ValueType, found: &Value<N>, span: Span) -> EvalError {
+    EvalError::new(
+        EvalErrorKind::TypeMismatch {
+            expected,
+            found: found.value_type(),
+        },
+        span,
+    )
 }
 
-impl Visitor<i32> for Executor {
-    /// return the actual value of the node directly
-    fn visit_num(&mut self, value: i32, _: &str) -> i32 {
-        value
+impl<N: Number> Visitor<Result<Value<N>, EvalError>> for Executor<'_, N> {
+    fn visit_num(&mut self, value: &Num, raw: &str, span: Span) -> Result<Value<N>, EvalError> {
+        literal(value, raw, false, self.options.overflow)
+            .map(Value::Num)
+            .ok_or(EvalError::new(EvalErrorKind::InvalidLiteral, span))
+    }
+
+    fn visit_variable(&mut self, name: &str, span: Span) -> Result<Value<N>, EvalError> {
+        self.env
+            .resolve(name)
+            .map(Value::Num)
+            .ok_or(EvalError::new(EvalErrorKind::UnboundVariable, span))
+    }
+
+    /// the function and its arity are checked before any argument is evaluated,
+    /// and the arguments are evaluated from left to right, each of them must be a number
+    fn visit_call(&mut self, name: &str, args: &[Node], span: Span) -> Result<Value<N>, EvalError> {
+        let (functions, arity) = match self.functions {
+            Some(functions) if functions.contains(name) => {
+                (functions, functions.arity(name).unwrap())
+            }
+            _ => return Err(EvalError::new(EvalErrorKind::UnknownFunction, span)),
+        };
+        if !arity.accepts(args.len()) {
+            let kind = EvalErrorKind::ArityMismatch {
+                expected: arity,
+                found: args.len(),
+            };
+            return Err(EvalError::new(kind, span));
+        }
+        let values = args
+            .iter()
+            .map(|arg| self.number(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        functions
+            .call(name, values, self.options.overflow)
+            .map(Value::Num)
+            .map_err(|kind| EvalError::new(kind, span))
+    }
+
+    /// the negation of a literal is parsed as a whole,
+    /// so that the minimum value is accepted, e.g. `-2147483648` for `i32`
+    fn visit_unary(&mut self, op: &str, operand: &Node, span: Span) -> Result<Value<N>, EvalError> {
+        if let ("-", Node::Literal { value, raw, .. }) = (op, operand) {
+            return literal(value, raw, true, self.options.overflow)
+                .map(Value::Num)
+                .ok_or(EvalError::new(EvalErrorKind::InvalidLiteral, span));
+        }
+        if op == "!" {
+            return Ok(Value::Bool(!self.boolean(operand)?));
+        }
+        let value = self.number(operand)?;
+        let result = match op {
+            "-" => value.neg(self.options.overflow),
+            "+" => Ok(value),
+            "~" => value.bit_not(),
+            _ => panic!("unexpected operator: {}", op),
+        };
+        result
+            .map(Value::Num)
+            .map_err(|kind| EvalError::new(kind, span))
     }
 
     /// operate the expression according to the operator
@@ -25,47 +221,730 @@ impl Visitor<i32> for Executor {
     ///
     ///            1 + 2 + 3
     ///
-    ///	              +
-    ///	            /   \
-    ///	           +     3
-    ///	          / \
+    ///               +
+    ///             /   \
+    ///            +     3
+    ///           / \
     ///          1   2
     ///
-    fn visit_expr(&mut self, left: &Node, op: &str, right: &Node) -> i32 {
-        let left = self.visit(left);
-        let right = self.visit(right);
+    /// `&&` and `||` are short-circuit, the right node is not visited if the left one decides the result,
+    /// `==` and `!=` compare two numbers or two booleans, the other operators only accept numbers
+    ///
+    /// the overflow is handled according to `EvalOptions::overflow`,
+    /// and the error is reported with the span of current expression,
+    /// or the span of the operand whose type is wrong
+    fn visit_expr(
+        &mut self,
+        left: &Node,
+        op: &str,
+        right: &Node,
+        span: Span,
+    ) -> Result<Value<N>, EvalError> {
         match op {
-            "+" => left + right,
-            "-" => left - right,
-            "*" => left * right,
-            "/" => left / right,
+            "&&" => return Ok(Value::Bool(self.boolean(left)? && self.boolean(right)?)),
+            "||" => return Ok(Value::Bool(self.boolean(left)? || self.boolean(right)?)),
+            "==" | "!=" => {
+                // the right operand is expected to have the same type as the left one
+                let equal = match (self.visit(left)?, self.visit(right)?) {
+                    (Value::Num(a), Value::Num(b)) => a == b,
+                    (Value::Bool(a), Value::Bool(b)) => a == b,
+                    (a, b) => return Err(type_mismatch(a.value_type(), &b, right.span())),
+                };
+                return Ok(Value::Bool(equal == (op == "==")));
+            }
+            _ => {}
+        }
+        let left = self.number(left)?;
+        let right = self.number(right)?;
+        let overflow = self.options.overflow;
+        let result = match op {
+            "<" => return Ok(Value::Bool(left < right)),
+            "<=" => return Ok(Value::Bool(left <= right)),
+            ">" => return Ok(Value::Bool(left > right)),
+            ">=" => return Ok(Value::Bool(left >= right)),
+            "+" => left.add(right, overflow),
+            "-" => left.sub(right, overflow),
+            "*" => left.mul(right, overflow),
+            "/" => left.div(right, overflow),
+            "**" => left.pow(right, overflow),
+            "//" => left.int_div(right, self.options.division, overflow),
+            "%" => left.rem(right, self.options.division, overflow),
+            "&" => left.bit_and(right),
+            "|" => left.bit_or(right),
+            "^" => left.bit_xor(right),
+            "<<" => left.shl(right),
+            ">>" => left.shr(right),
 
             _ => panic!("unexpected operator: {}", op),
+        };
+        result
+            .map(Value::Num)
+            .map_err(|kind| EvalError::new(kind, span))
+    }
+
+    /// the condition must be a boolean, and only the chosen branch is visited,
+    /// e.g. `b == 0 ? 0 : 10 / b` never divides by zero
+    fn visit_conditional(
+        &mut self,
+        condition: &Node,
+        then: &Node,
+        otherwise: &Node,
+        _: Span,
+    ) -> Result<Value<N>, EvalError> {
+        match self.boolean(condition)? {
+            true => self.visit(then),
+            false => self.visit(otherwise),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    use crate::bigint::BigInt;
+    use crate::error::{EvalError, EvalErrorKind};
     use crate::parser::Node;
-    use crate::traversal::eval::Executor;
+    use crate::span::Span;
+    use crate::traversal::eval::{DivisionMode, EvalOptions, Executor, OverflowMode};
+    use crate::traversal::function::{Arity, Registry};
+    use crate::traversal::value::{Value, ValueType};
     use crate::{lex, syntax};
 
     fn get_node(code: &str) -> Node {
-        syntax(lex(code).unwrap()).unwrap()
+        syntax(&lex(code).unwrap()).unwrap()
     }
 
     #[test]
     fn smoke() {
-        let mut e = Executor::new();
+        let mut e = Executor::<i32>::new();
+
+        assert_eq!(Ok(3), e.eval(&get_node("2 + 1")));
+        assert_eq!(Ok(1), e.eval(&get_node("2 - 1")));
+        assert_eq!(Ok(2), e.eval(&get_node("2 * 1")));
+        assert_eq!(Ok(2), e.eval(&get_node("2 / 1")));
+
+        assert_eq!(Ok(7), e.eval(&get_node("1 + 2 * 3")));
+        assert_eq!(Ok(5), e.eval(&get_node("1 * ( 2 + 3 )")));
+        assert_eq!(Ok(14), e.eval(&get_node("1 * ( 2 * ( 3 + 4 ))")));
+    }
+
+    #[test]
+    fn unary() {
+        let mut e = Executor::<i32>::new();
+        let env = HashMap::from([("x", 4)]);
+        let mut with_x = Executor::<i32>::with_env(&env, EvalOptions::default());
+
+        assert_eq!(Ok(-3), e.eval(&get_node("-(1 + 2)")));
+        assert_eq!(Ok(3), e.eval(&get_node("- -3")));
+        assert_eq!(Ok(2), e.eval(&get_node("+ 2")));
+        assert_eq!(Ok(-8), with_x.eval(&get_node("2 * -x")));
+        assert_eq!(Ok(i32::MIN), e.eval(&get_node("-2147483648")));
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::InvalidLiteral,
+                Span::new(1, 11)
+            )),
+            e.eval(&get_node("+2147483648"))
+        );
+        assert_eq!(
+            Err(EvalError::new(EvalErrorKind::Overflow, Span::new(0, 18))),
+            e.eval(&get_node("-(-2147483647 - 1)"))
+        );
+
+        let mut e = Executor::<i32>::with_options(EvalOptions {
+            overflow: OverflowMode::Wrapping,
+            ..EvalOptions::default()
+        });
+        assert_eq!(Ok(i32::MIN), e.eval(&get_node("-(-2147483647 - 1)")));
+    }
+
+    #[test]
+    fn power() {
+        let mut e = Executor::<i32>::new();
+
+        assert_eq!(Ok(512), e.eval(&get_node("2 ** 3 ** 2")));
+        assert_eq!(Ok(-4), e.eval(&get_node("-2 ** 2")));
+        assert_eq!(Ok(4), e.eval(&get_node("(-2) ** 2")));
+        assert_eq!(Ok(i32::MIN), e.eval(&get_node("(-2) ** 31")));
+        assert_eq!(Ok(1), e.eval(&get_node("0 ** 0")));
+        assert_eq!(Ok(0), e.eval(&get_node("2 ** -1")));
+        assert_eq!(Ok(-1), e.eval(&get_node("(-1) ** -3")));
+        assert_eq!(
+            Err(EvalError::new(EvalErrorKind::Overflow, Span::new(0, 7))),
+            e.eval(&get_node("2 ** 31"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::DivisionByZero,
+                Span::new(0, 7)
+            )),
+            e.eval(&get_node("0 ** -1"))
+        );
+
+        let mut e = Executor::<i32>::with_options(EvalOptions {
+            overflow: OverflowMode::Wrapping,
+            ..EvalOptions::default()
+        });
+        assert_eq!(Ok(0), e.eval(&get_node("2 ** 32")));
+        assert_eq!(Ok(3_i32.wrapping_pow(40)), e.eval(&get_node("3 ** 40")));
+
+        let mut e = Executor::<i32>::with_options(EvalOptions {
+            overflow: OverflowMode::Saturating,
+            ..EvalOptions::default()
+        });
+        assert_eq!(Ok(i32::MIN), e.eval(&get_node("(-3) ** 41")));
+
+        let mut e = Executor::<f64>::new();
+        assert_eq!(Ok(0.5), e.eval(&get_node("2 ** -1")));
+        assert_eq!(Ok(3.0), e.eval(&get_node("9 ** 0.5")));
+
+        let mut e = Executor::<BigInt>::new();
+        assert_eq!(
+            Ok("1267650600228229401496703205376".parse().unwrap()),
+            e.eval(&get_node("2 ** 100"))
+        );
+        assert_eq!(Ok(BigInt::from(-1)), e.eval(&get_node("(-1) ** -3")));
+    }
+
+    #[test]
+    fn int_div_and_rem() {
+        let mut e = Executor::<i32>::new();
+        assert_eq!(Ok(-4), e.eval(&get_node("-7 // 2")));
+        assert_eq!(Ok(1), e.eval(&get_node("-7 % 2")));
+        assert_eq!(Ok(-1), e.eval(&get_node("7 % -2")));
+        assert_eq!(Ok(-3), e.eval(&get_node("-7 / 2")));
+        assert_eq!(Ok(7), e.eval(&get_node("7 // 2 * 2 + 7 % 2")));
+        assert_eq!(Ok(0), e.eval(&get_node("(-2147483647 - 1) % -1")));
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::DivisionOverflow,
+                Span::new(0, 23)
+            )),
+            e.eval(&get_node("(-2147483647 - 1) // -1"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::DivisionByZero,
+                Span::new(0, 5)
+            )),
+            e.eval(&get_node("1 % 0"))
+        );
+
+        let mut e = Executor::<i32>::with_options(EvalOptions {
+            division: DivisionMode::Truncated,
+            ..EvalOptions::default()
+        });
+        assert_eq!(Ok(-3), e.eval(&get_node("-7 // 2")));
+        assert_eq!(Ok(-1), e.eval(&get_node("-7 % 2")));
+        assert_eq!(Ok(1), e.eval(&get_node("7 % -2")));
+
+        let mut e = Executor::<i32>::with_options(EvalOptions {
+            division: DivisionMode::Euclidean,
+            ..EvalOptions::default()
+        });
+        assert_eq!(Ok(-4), e.eval(&get_node("-7 // 2")));
+        assert_eq!(Ok(1), e.eval(&get_node("-7 % 2")));
+        assert_eq!(Ok(-3), e.eval(&get_node("7 // -2")));
+        assert_eq!(Ok(1), e.eval(&get_node("7 % -2")));
+        assert_eq!(Ok(4), e.eval(&get_node("-7 // -2")));
+        assert_eq!(Ok(1), e.eval(&get_node("-7 % -2")));
+
+        let mut e = Executor::<f64>::new();
+        assert_eq!(Ok(-4.0), e.eval(&get_node("-7 // 2")));
+        assert_eq!(Ok(0.5), e.eval(&get_node("-5.5 % 2")));
+
+        let mut e = Executor::<BigInt>::new();
+        assert_eq!(
+            Ok(BigInt::from(-33333333333333333334_i128)),
+            e.eval(&get_node("-100000000000000000000 // 3"))
+        );
+        assert_eq!(
+            Ok(BigInt::from(2)),
+            e.eval(&get_node("-100000000000000000000 % 3"))
+        );
+    }
+
+    #[test]
+    fn report_overflow() {
+        let mut e = Executor::<i32>::new();
+
+        assert_eq!(
+            Err(EvalError::new(EvalErrorKind::Overflow, Span::new(0, 14))),
+            e.eval(&get_node("2147483647 + 1"))
+        );
+        assert_eq!(
+            Err(EvalError::new(EvalErrorKind::Overflow, Span::new(0, 15))),
+            e.eval(&get_node("-2147483647 - 2"))
+        );
+        assert_eq!(
+            Err(EvalError::new(EvalErrorKind::Overflow, Span::new(4, 17))),
+            e.eval(&get_node("1 + 65536 * 65536"))
+        );
+    }
+
+    #[test]
+    fn report_division_by_zero() {
+        let mut e = Executor::<i32>::new();
+
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::DivisionByZero,
+                Span::new(4, 11)
+            )),
+            e.eval(&get_node("1 + (1 / 0)"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::DivisionByZero,
+                Span::new(0, 11)
+            )),
+            e.eval(&get_node("1 / (1 - 1)"))
+        );
+    }
+
+    #[test]
+    fn report_division_overflow() {
+        let mut e = Executor::<i32>::new();
+
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::DivisionOverflow,
+                Span::new(0, 22)
+            )),
+            e.eval(&get_node("(-2147483647 - 1) / -1"))
+        );
+    }
+
+    #[test]
+    fn wrapping() {
+        let mut e = Executor::<i32>::with_options(EvalOptions {
+            overflow: OverflowMode::Wrapping,
+            ..EvalOptions::default()
+        });
+
+        assert_eq!(Ok(i32::MIN), e.eval(&get_node("2147483647 + 1")));
+        assert_eq!(Ok(i32::MAX), e.eval(&get_node("-2147483647 - 2")));
+        assert_eq!(Ok(0), e.eval(&get_node("65536 * 65536")));
+        assert_eq!(Ok(i32::MIN), e.eval(&get_node("(-2147483647 - 1) / -1")));
+        assert!(e.eval(&get_node("1 / 0")).is_err());
+    }
+
+    #[test]
+    fn saturating() {
+        let mut e = Executor::<i32>::with_options(EvalOptions {
+            overflow: OverflowMode::Saturating,
+            ..EvalOptions::default()
+        });
+
+        assert_eq!(Ok(i32::MAX), e.eval(&get_node("2147483647 + 1")));
+        assert_eq!(Ok(i32::MIN), e.eval(&get_node("-2147483647 - 2")));
+        assert_eq!(Ok(i32::MIN), e.eval(&get_node("65536 * -65536")));
+        assert_eq!(Ok(i32::MAX), e.eval(&get_node("(-2147483647 - 1) / -1")));
+        assert!(e.eval(&get_node("1 / 0")).is_err());
+    }
+
+    #[test]
+    fn reject_float_literal() {
+        let mut e = Executor::<i32>::new();
+
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::InvalidLiteral,
+                Span::new(4, 7)
+            )),
+            e.eval(&get_node("1 + 1.5"))
+        );
+    }
+
+    #[test]
+    fn float() {
+        let mut e = Executor::<f64>::new();
+
+        assert_eq!(Ok(3.0), e.eval(&get_node("1.5 * 2")));
+        assert_eq!(Ok(0.5), e.eval(&get_node("1 / 2")));
+        assert_eq!(Ok(0.001), e.eval(&get_node("1e-3")));
+        assert_eq!(Ok(-2.5), e.eval(&get_node("1 - 3.5")));
+        assert_eq!(Ok(f64::INFINITY), e.eval(&get_node("1 / 0")));
+        assert!(e.eval(&get_node("0 / 0.0")).unwrap().is_nan());
+    }
+
+    #[test]
+    fn big_integer() {
+        let mut e = Executor::<BigInt>::new();
+        assert_eq!(
+            Ok("9999999999999999999800000000000000000001".parse().unwrap()),
+            e.eval(&get_node("99999999999999999999 * 99999999999999999999"))
+        );
+        assert_eq!(
+            Ok(BigInt::from(-33333333333333333333_i128)),
+            e.eval(&get_node("-99999999999999999999 / 3"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::DivisionByZero,
+                Span::new(0, 24)
+            )),
+            e.eval(&get_node("99999999999999999999 / 0"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::InvalidLiteral,
+                Span::new(4, 7)
+            )),
+            e.eval(&get_node("1 + 1.5"))
+        );
+    }
+
+    #[test]
+    fn wider_integer() {
+        let mut e = Executor::<i64>::new();
+        assert_eq!(Ok(6000000000), e.eval(&get_node("3000000000 * 2")));
+
+        let mut e = Executor::<i128>::new();
+        assert_eq!(
+            Ok(i64::MAX as i128 + 1),
+            e.eval(&get_node("9223372036854775807 + 1"))
+        );
+    }
+
+    #[test]
+    fn variable() {
+        let env = HashMap::from([("price".to_string(), 25), ("qty".to_string(), 4)]);
+        let mut e = Executor::<i32>::with_env(&env, EvalOptions::default());
+
+        assert_eq!(Ok(110), e.eval(&get_node("price * qty + 10")));
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::UnboundVariable,
+                Span::new(8, 16)
+            )),
+            e.eval(&get_node("price + shipping"))
+        );
+
+        let index = |name: &str| name.strip_prefix("x").and_then(|n| n.parse::<i64>().ok());
+        let mut e = Executor::<i64>::with_env(&index, EvalOptions::default());
+        assert_eq!(Ok(15), e.eval(&get_node("x7 + x8")));
+
+        let mut e = Executor::<i32>::new();
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::UnboundVariable,
+                Span::new(4, 5)
+            )),
+            e.eval(&get_node("1 + x"))
+        );
+    }
+
+    #[test]
+    fn call() {
+        let mut functions = Registry::<i32>::with_builtins();
+        functions.register("inc", Arity::Exact(1), |args, overflow| {
+            crate::traversal::number::Number::add(args[0], 1, overflow)
+        });
+        let env = HashMap::from([("x", -5)]);
+        let mut e = Executor::with_registry(&env, &functions, EvalOptions::default());
+
+        assert_eq!(Ok(6), e.eval(&get_node("max(abs(x), inc(5), 2 * 2)")));
+        assert_eq!(
+            Err(EvalError::new(EvalErrorKind::Overflow, Span::new(4, 19))),
+            e.eval(&get_node("1 + inc(2147483647)"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::ArityMismatch {
+                    expected: Arity::Exact(1),
+                    found: 2
+                },
+                Span::new(0, 9)
+            )),
+            e.eval(&get_node("abs(1, 2)"))
+        );
+        // the arity is checked before the arguments are evaluated too
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::ArityMismatch {
+                    expected: Arity::Exact(1),
+                    found: 2
+                },
+                Span::new(0, 13)
+            )),
+            e.eval(&get_node("abs(1 / 0, 2)"))
+        );
+        assert_eq!(Ok(1024), e.eval(&get_node("pow(2, 10)")));
+        // the unknown function is reported before its arguments are evaluated
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::UnknownFunction,
+                Span::new(0, 9)
+            )),
+            e.eval(&get_node("sqrt(1/0)"))
+        );
+
+        let mut e = Executor::<i32>::new();
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::UnknownFunction,
+                Span::new(0, 6)
+            )),
+            e.eval(&get_node("abs(1)"))
+        );
+    }
+
+    #[test]
+    fn comparison_and_logic() {
+        let env = HashMap::from([("a", 4), ("b", 0)]);
+        let mut e = Executor::<i32>::with_env(&env, EvalOptions::default());
+
+        assert_eq!(
+            Ok(Value::Bool(false)),
+            e.eval_value(&get_node("(a + 1) * 2 >= 10 && b != 0"))
+        );
+        assert_eq!(
+            Ok(Value::Bool(true)),
+            e.eval_value(&get_node("(a + 1) * 2 >= 10 || b != 0"))
+        );
+        assert_eq!(Ok(Value::Num(10)), e.eval_value(&get_node("(a + 1) * 2")));
+        assert_eq!(
+            Ok(Value::Bool(true)),
+            e.eval_value(&get_node("1 < 2 == 2 > 1"))
+        );
+        assert_eq!(Ok(Value::Bool(true)), e.eval_value(&get_node("!(a <= 3)")));
+        assert_eq!(Ok(Value::Bool(true)), e.eval_value(&get_node("!!(a == 4)")));
+        assert_eq!(Ok(Value::Bool(false)), e.eval_value(&get_node("a < 4")));
+
+        let mut e = Executor::<f64>::new();
+        assert_eq!(
+            Ok(Value::Bool(false)),
+            e.eval_value(&get_node("0 / 0 == 0 / 0"))
+        );
+        assert_eq!(
+            Ok(Value::Bool(true)),
+            e.eval_value(&get_node("0.1 + 0.2 > 0.3"))
+        );
+    }
+
+    #[test]
+    fn short_circuit() {
+        let env = HashMap::from([("b", 0)]);
+        let mut e = Executor::<i32>::with_env(&env, EvalOptions::default());
+
+        assert_eq!(
+            Ok(Value::Bool(false)),
+            e.eval_value(&get_node("b != 0 && 10 / b > 1"))
+        );
+        assert_eq!(
+            Ok(Value::Bool(true)),
+            e.eval_value(&get_node("b == 0 || 10 / b > 1"))
+        );
+        // the right operand is not even checked for its type
+        assert_eq!(
+            Ok(Value::Bool(false)),
+            e.eval_value(&get_node("b > 0 && b"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::DivisionByZero,
+                Span::new(10, 16)
+            )),
+            e.eval_value(&get_node("b == 0 && 10 / b > 1"))
+        );
+    }
+
+    #[test]
+    fn report_type_mismatch() {
+        let mut e = Executor::<i32>::new();
+        let mismatch = |expected, found, start, end| {
+            Err(EvalError::new(
+                EvalErrorKind::TypeMismatch { expected, found },
+                Span::new(start, end),
+            ))
+        };
+
+        assert_eq!(
+            mismatch(ValueType::Bool, ValueType::Number, 1, 2),
+            e.eval_value(&get_node("!1"))
+        );
+        assert_eq!(
+            mismatch(ValueType::Bool, ValueType::Number, 0, 1),
+            e.eval_value(&get_node("1 && 1 < 2"))
+        );
+        assert_eq!(
+            mismatch(ValueType::Number, ValueType::Bool, 0, 7),
+            e.eval_value(&get_node("(1 < 2) + 1"))
+        );
+        assert_eq!(
+            mismatch(ValueType::Number, ValueType::Bool, 1, 8),
+            e.eval_value(&get_node("-(1 < 2)"))
+        );
+        assert_eq!(
+            mismatch(ValueType::Number, ValueType::Bool, 5, 10),
+            e.eval_value(&get_node("1 == 1 < 2"))
+        );
+        assert_eq!(
+            mismatch(ValueType::Number, ValueType::Bool, 4, 11),
+            e.eval_value(&get_node("1 < (2 < 3)"))
+        );
+        // a boolean is not accepted where a number is expected
+        assert_eq!(
+            mismatch(ValueType::Number, ValueType::Bool, 0, 5),
+            e.eval(&get_node("1 < 2")).map(Value::Num)
+        );
+
+        let functions = Registry::<i32>::with_builtins();
+        let mut e = Executor::with_registry(&(), &functions, EvalOptions::default());
+        assert_eq!(
+            mismatch(ValueType::Number, ValueType::Bool, 4, 9),
+            e.eval_value(&get_node("abs(1 > 2)"))
+        );
+    }
+
+    #[test]
+    fn conditional() {
+        let env = HashMap::from([("qty", 150.0), ("price", 20.0)]);
+        let mut e = Executor::<f64>::with_env(&env, EvalOptions::default());
+        assert_eq!(
+            Ok(18.0),
+            e.eval(&get_node("qty > 100 ? price * 0.9 : price"))
+        );
+
+        let env = HashMap::from([("b", 0)]);
+        let mut e = Executor::<i32>::with_env(&env, EvalOptions::default());
+        assert_eq!(Ok(0), e.eval(&get_node("b == 0 ? 0 : 10 / b")));
+        assert_eq!(Ok(3), e.eval(&get_node("b > 0 ? 1 : b < 0 ? 2 : 3")));
+        assert_eq!(
+            Ok(Value::Bool(true)),
+            e.eval_value(&get_node("b == 0 ? b < 1 : 2"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::TypeMismatch {
+                    expected: ValueType::Bool,
+                    found: ValueType::Number
+                },
+                Span::new(0, 1)
+            )),
+            e.eval(&get_node("b ? 1 : 2"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::DivisionByZero,
+                Span::new(9, 15)
+            )),
+            e.eval(&get_node("b == 0 ? 10 / b : 0"))
+        );
+    }
+
+    #[test]
+    fn bitwise() {
+        let env = HashMap::from([("mask", 0b0110)]);
+        let mut e = Executor::<i32>::with_env(&env, EvalOptions::default());
+
+        assert_eq!(Ok(0b1_1001), e.eval(&get_node("(1 << 4) | 15 & ~mask")));
+        assert_eq!(Ok(6), e.eval(&get_node("5 ^ 3")));
+        assert_eq!(Ok(-1), e.eval(&get_node("-8 | 7")));
+        assert_eq!(Ok(-4), e.eval(&get_node("-16 >> 2")));
+        assert_eq!(Ok(i32::MIN), e.eval(&get_node("1 << 31")));
+        assert_eq!(Ok(-1), e.eval(&get_node("~0")));
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::ShiftOutOfRange,
+                Span::new(4, 13)
+            )),
+            e.eval(&get_node("1 + (1 << 32)"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::ShiftOutOfRange,
+                Span::new(0, 7)
+            )),
+            e.eval(&get_node("8 >> -1"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::TypeMismatch {
+                    expected: ValueType::Number,
+                    found: ValueType::Bool
+                },
+                Span::new(4, 12)
+            )),
+            e.eval(&get_node("1 & (1 == 1)"))
+        );
+
+        let mut e = Executor::<i64>::new();
+        assert_eq!(Ok(1 << 40), e.eval(&get_node("1 << 40")));
+
+        let mut e = Executor::<BigInt>::new();
+        assert_eq!(
+            Ok(BigInt::from(1_i128 << 100 | 0xff)),
+            e.eval(&get_node("1 << 100 | 255"))
+        );
+
+        let mut e = Executor::<f64>::new();
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::UnsupportedOperation,
+                Span::new(0, 2)
+            )),
+            e.eval(&get_node("~1"))
+        );
+    }
+
+    #[test]
+    fn radix_and_separator() {
+        let mut e = Executor::<i32>::new();
+        assert_eq!(Ok(255), e.eval(&get_node("0xFF")));
+        assert_eq!(Ok(0b1010 + 0o17), e.eval(&get_node("0b1010 + 0o17")));
+        assert_eq!(Ok(1_000_000), e.eval(&get_node("1_000_000")));
+        assert_eq!(Ok(i32::MIN), e.eval(&get_node("-0x8000_0000")));
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::InvalidLiteral,
+                Span::new(0, 11)
+            )),
+            e.eval(&get_node("0x8000_0000"))
+        );
+
+        // the bits of a radix literal are taken as the two's complement only when wrapping
+        let mut e = Executor::<i32>::with_options(EvalOptions {
+            overflow: OverflowMode::Wrapping,
+            ..EvalOptions::default()
+        });
+        assert_eq!(Ok(-1), e.eval(&get_node("0xFFFF_FFFF")));
+        assert_eq!(Ok(i32::MIN), e.eval(&get_node("0x8000_0000")));
+        assert_eq!(Ok(1), e.eval(&get_node("-0xFFFF_FFFF")));
+        assert_eq!(
+            Ok(-2),
+            e.eval(&get_node("0b1111_1111_1111_1111_1111_1111_1111_1110"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::InvalidLiteral,
+                Span::new(0, 13)
+            )),
+            e.eval(&get_node("0x1_0000_0000"))
+        );
+        assert_eq!(
+            Err(EvalError::new(
+                EvalErrorKind::InvalidLiteral,
+                Span::new(0, 10)
+            )),
+            e.eval(&get_node("4294967295"))
+        );
+        let mut e = Executor::<i32>::with_options(EvalOptions {
+            overflow: OverflowMode::Saturating,
+            ..EvalOptions::default()
+        });
+        assert!(e.eval(&get_node("0xFFFF_FFFF")).is_err());
 
-        assert_eq!(3, e.eval(&get_node("2 + 1")));
-        assert_eq!(1, e.eval(&get_node("2 - 1")));
-        assert_eq!(2, e.eval(&get_node("2 * 1")));
-        assert_eq!(2, e.eval(&get_node("2 / 1")));
+        let mut e = Executor::<BigInt>::new();
+        assert_eq!(
+            Ok(BigInt::from(i128::MAX)),
+            e.eval(&get_node("0x7FFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF"))
+        );
 
-        assert_eq!(7, e.eval(&get_node("1 + 2 * 3")));
-        assert_eq!(5, e.eval(&get_node("1 * ( 2 + 3 )")));
-        assert_eq!(14, e.eval(&get_node("1 * ( 2 * ( 3 + 4 ))")));
+        let mut e = Executor::<f64>::new();
+        assert_eq!(Ok(1000.5), e.eval(&get_node("1_000.5")));
+        assert_eq!(Ok(16.0), e.eval(&get_node("0x10")));
     }
 }
@@ -1,15 +1,55 @@
-use crate::parser::Node;
-use crate::syntax_kind::SyntaxKind;
+use std::cmp::Ordering;
+
+use crate::parser::{Node, Num};
+use crate::span::Span;
+use crate::syntax_kind::{Assoc, SyntaxKind};
+use crate::token;
 use crate::traversal::visitor::Visitor;
 
+/// the radix which the integer literals are written in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    /// keep the literal as it is written, e.g. `0xFF`, `1_000`
+    #[default]
+    Preserve,
+    /// e.g. `255`, without the digit separators
+    Decimal,
+    /// e.g. `0xFF`
+    Hexadecimal,
+    /// e.g. `0o377`
+    Octal,
+    /// e.g. `0b11111111`
+    Binary,
+}
+
+/// options to customize the formatting
+///
+/// # Example
+/// ```rust
+/// let options = FormatOptions {
+///     radix: Radix::Hexadecimal,
+/// };
+/// assert_eq!("0xFF & 0xF", format_with(&build_ast("255&0b1111").unwrap(), options));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// only for the integer literals, the float ones are always kept as they are
+    pub radix: Radix,
+}
+
 pub struct Formatter {
     output: String,
+    options: FormatOptions,
 }
 
 impl Formatter {
     pub fn new() -> Self {
+        Formatter::with_options(FormatOptions::default())
+    }
+    pub fn with_options(options: FormatOptions) -> Self {
         Formatter {
             output: String::new(),
+            options,
         }
     }
     pub fn format(&mut self, node: &Node) -> &str {
@@ -28,51 +68,132 @@ impl Formatter {
         self.visit(node);
         self.push(")");
     }
+    /// visit a operand, and wrap it in parenthesis if required
+    fn push_operand(&mut self, op: &str, operand: &Node, side: Assoc) {
+        if Formatter::need_paren(op, operand, side) {
+            self.push_paren_expr(operand);
+        } else {
+            self.visit(operand);
+        }
+    }
     fn ws(&mut self) {
         self.push(" ");
     }
+
+    /// whether the operand of `op` must be wrapped in parenthesis,
+    /// so that parsing the output again yields exactly the same tree
+    ///
+    /// side: the operand is the left one or the right one
+    fn need_paren(op: &str, operand: &Node, side: Assoc) -> bool {
+        match operand {
+            // the lower priority expression must be wrapped, e.g. `(1 + 2) * 3`
+            //
+            // for the same priority, only the operand on the side of the associativity can go without,
+            // e.g. `1 - 2 - 3` is `(1 - 2) - 3`, so `1 - (2 - 3)` must keep the parenthesis
+            //
+            // even for `+` and `*`, `1 + (2 + 3)` is not regrouped as `1 + 2 + 3`,
+            // it changes the order of evaluation which may overflow at a different place,
+            // and regrouping is wrong for the non-commutative `-` and `/` anyway, e.g. `1 * (4 / 2)`
+            Node::Expr { op: next_op, .. } => {
+                let next_op = next_op.into_str();
+                match SyntaxKind::get_op_priority(op).cmp(&SyntaxKind::get_op_priority(next_op)) {
+                    Ordering::Greater => true,
+                    Ordering::Equal => SyntaxKind::get_op_assoc(op) != side,
+                    Ordering::Less => false,
+                }
+            }
+            // the prefix operator on the right, e.g. `1 + (-1)` rather than `1 + -1`,
+            // it binds tighter than any binary operator except the power, so the left one can go without,
+            // but `(-2) ** 2` must keep the parenthesis
+            //
+            // `!` and `~` can not be mistaken for a binary operator, e.g. `a && !b`, `a & ~b`
+            Node::Unary { op: prefix, .. } => {
+                let bare = *prefix == token!["!"] || *prefix == token!["~"];
+                (side == Assoc::Right && !bare) || op == "**"
+            }
+            // it has the lowest priority, e.g. `(a ? 1 : 2) + 3`
+            Node::Conditional { .. } => true,
+            Node::Literal { .. } | Node::Variable { .. } | Node::Call { .. } => false,
+        }
+    }
 }
 
 impl Visitor<()> for Formatter {
-    fn visit_num(&mut self, _: i32, raw: &str) {
-        self.push(raw)
+    /// the integer literal is written in the radix of `FormatOptions::radix`
+    fn visit_num(&mut self, value: &Num, raw: &str, _: Span) {
+        let int = match value {
+            Num::Int(int) => int,
+            Num::Float(_) => return self.push(raw),
+        };
+        match self.options.radix {
+            Radix::Preserve => self.push(raw),
+            Radix::Decimal => self.push(&int.to_string()),
+            Radix::Hexadecimal => self.push(&format!("0x{}", int.to_str_radix(16))),
+            Radix::Octal => self.push(&format!("0o{}", int.to_str_radix(8))),
+            Radix::Binary => self.push(&format!("0b{}", int.to_str_radix(2))),
+        }
+    }
+
+    fn visit_variable(&mut self, name: &str, _: Span) {
+        self.push(name)
+    }
+
+    /// the operand is wrapped unless it is a single term, e.g. `-(1 + 2)`, `-(-1)`, `-x`
+    fn visit_unary(&mut self, op: &str, operand: &Node, _: Span) {
+        self.push(op);
+        match operand {
+            Node::Unary { .. } | Node::Expr { .. } | Node::Conditional { .. } => {
+                self.push_paren_expr(operand)
+            }
+            _ => self.visit(operand),
+        }
     }
 
-    fn visit_expr(&mut self, left: &Node, op: &str, right: &Node) {
-        self.visit(left);
+    /// the arguments are separated by the commas, so none of them needs parenthesis
+    fn visit_call(&mut self, name: &str, args: &[Node], _: Span) {
+        self.push(name);
+        self.push("(");
+        for (idx, arg) in args.iter().enumerate() {
+            if idx > 0 {
+                self.push(", ");
+            }
+            self.visit(arg);
+        }
+        self.push(")");
+    }
+
+    fn visit_expr(&mut self, left: &Node, op: &str, right: &Node, _: Span) {
+        self.push_operand(op, left, Assoc::Left);
         self.ws();
         self.push(op);
         self.ws();
+        self.push_operand(op, right, Assoc::Right);
+    }
 
-        // wrap a expression in a parenthsis
-        // while:
-        //    (1). the current expression has a higher priority than the right expression
-        //    (2). current expression is a negative number
-        match right {
-            Node::Expr { op: next_op, .. }
-                if SyntaxKind::get_op_priority(op)
-                    > SyntaxKind::get_op_priority(next_op.into_str()) =>
-            {
-                self.push_paren_expr(right);
-            }
-            Node::Literal { raw, .. } if raw.chars().nth(0) == Some('-') => {
-                self.push_paren_expr(right);
-            }
-            _ => {
-                self.visit(right);
-            }
+    /// the branches are delimited by `?` and `:`, so only a conditional in the condition is wrapped,
+    /// e.g. `(a ? b : c) ? d : e`, but `a ? b ? c : d : e ? f : g`
+    fn visit_conditional(&mut self, condition: &Node, then: &Node, otherwise: &Node, _: Span) {
+        match condition {
+            Node::Conditional { .. } => self.push_paren_expr(condition),
+            _ => self.visit(condition),
         }
+        self.push(" ? ");
+        self.visit(then);
+        self.push(" : ");
+        self.visit(otherwise);
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::parser::Node;
-    use crate::traversal::format::Formatter;
+    use crate::parser::{Node, Num, MAX_DEPTH};
+    use crate::span::Span;
+    use crate::syntax_kind::*;
+    use crate::traversal::format::{FormatOptions, Formatter, Radix};
     use crate::{lex, syntax};
 
     fn get_node(code: &str) -> Node {
-        syntax(lex(code).unwrap()).unwrap()
+        syntax(&lex(code).unwrap()).unwrap()
     }
 
     #[test]
@@ -87,7 +208,10 @@ mod tests {
         assert_eq!("1 * (2 + 3) * 4", f.format(&get_node("1*(2+3)*4")));
 
         let mut f = Formatter::new();
-        assert_eq!("1 * 2 * (3 + 4)", f.format(&get_node("1* ( 2 * ( 3 + 4))")));
+        assert_eq!(
+            "1 * (2 * (3 + 4))",
+            f.format(&get_node("1* ( 2 * ( 3 + 4))"))
+        );
     }
 
     #[test]
@@ -97,5 +221,391 @@ mod tests {
 
         let mut f = Formatter::new();
         assert_eq!("-1 + 1", f.format(&get_node("-1+1")));
+
+        let mut f = Formatter::new();
+        assert_eq!("-(1 + 2) * (-x)", f.format(&get_node("-(1+2)*-x")));
+
+        let mut f = Formatter::new();
+        assert_eq!("-(-3) - (+4)", f.format(&get_node("- -3 - +4")));
+    }
+
+    #[test]
+    fn allow_variable() {
+        let mut f = Formatter::new();
+        assert_eq!(
+            "price * (qty - 1) + shipping",
+            f.format(&get_node("price*(qty-1)+shipping"))
+        );
+    }
+
+    #[test]
+    fn allow_call() {
+        let mut f = Formatter::new();
+        assert_eq!(
+            "max(1 + 2, -3) * pow(2, min())",
+            f.format(&get_node("max( 1+2 ,-3 )*pow(2,min())"))
+        );
+    }
+
+    #[test]
+    fn wrap_left_operand() {
+        let mut f = Formatter::new();
+        assert_eq!("(1 + 2) * 3", f.format(&get_node("(1+2)*3")));
+
+        let mut f = Formatter::new();
+        assert_eq!("(1 - 2) / (3 + 4)", f.format(&get_node("(1-2)/(3+4)")));
+    }
+
+    #[test]
+    fn respect_associativity() {
+        let mut f = Formatter::new();
+        assert_eq!("1 - 2 - 3", f.format(&get_node("(1-2)-3")));
+
+        let mut f = Formatter::new();
+        assert_eq!("1 - (2 - 3)", f.format(&get_node("1-(2-3)")));
+
+        let mut f = Formatter::new();
+        assert_eq!("8 / (4 / 2)", f.format(&get_node("8/(4/2)")));
+
+        let mut f = Formatter::new();
+        assert_eq!("1 * (4 / 2)", f.format(&get_node("1*(4/2)")));
+
+        let mut f = Formatter::new();
+        assert_eq!("1 + (2 + 3)", f.format(&get_node("1+(2+3)")));
+    }
+
+    #[test]
+    fn power() {
+        let mut f = Formatter::new();
+        assert_eq!("2 ** 3 ** 2", f.format(&get_node("2**(3**2)")));
+
+        let mut f = Formatter::new();
+        assert_eq!("(2 ** 3) ** 2", f.format(&get_node("(2**3)**2")));
+
+        let mut f = Formatter::new();
+        assert_eq!("(-2) ** 2", f.format(&get_node("(-2)**2")));
+
+        let mut f = Formatter::new();
+        assert_eq!("-(2 ** 2)", f.format(&get_node("-2**2")));
+
+        let mut f = Formatter::new();
+        assert_eq!("2 ** (-1) * 3", f.format(&get_node("2**-1*3")));
+    }
+
+    #[test]
+    fn int_div_and_rem() {
+        let mut f = Formatter::new();
+        assert_eq!("7 // 2 % 3 * 4", f.format(&get_node("7//2%3*4")));
+
+        let mut f = Formatter::new();
+        assert_eq!("7 % (2 // 3)", f.format(&get_node("7%(2//3)")));
+
+        let mut f = Formatter::new();
+        assert_eq!("(7 + 1) % 2", f.format(&get_node("(7+1)%2")));
+    }
+
+    #[test]
+    fn comparison_and_logic() {
+        let mut f = Formatter::new();
+        assert_eq!(
+            "(a + 1) * 2 >= 10 && b != 0",
+            f.format(&get_node("(a+1)*2>=10&&b!=0"))
+        );
+
+        let mut f = Formatter::new();
+        assert_eq!("a || b && !c", f.format(&get_node("a||(b&&!c)")));
+
+        let mut f = Formatter::new();
+        assert_eq!(
+            "(a || b) && !(c < 1)",
+            f.format(&get_node("(a||b)&&!(c<1)"))
+        );
+
+        let mut f = Formatter::new();
+        assert_eq!("(!a) ** 2 == 1 < 2", f.format(&get_node("(!a)**2==(1<2)")));
+    }
+
+    #[test]
+    fn bitwise() {
+        let mut f = Formatter::new();
+        assert_eq!(
+            "1 << 4 | 15 & ~mask",
+            f.format(&get_node("(1<<4)|15&~mask"))
+        );
+
+        let mut f = Formatter::new();
+        assert_eq!(
+            "1 << 4 | (15 | 1) ^ 2",
+            f.format(&get_node("((1<<4)|((15|1)^2))"))
+        );
+
+        let mut f = Formatter::new();
+        assert_eq!("(a & 1) == 1", f.format(&get_node("(a&1)==1")));
+
+        let mut f = Formatter::new();
+        assert_eq!("~(a >> 1) - ~b", f.format(&get_node("~(a>>1)-~b")));
+    }
+
+    #[test]
+    fn conditional() {
+        let mut f = Formatter::new();
+        assert_eq!(
+            "qty > 100 ? price * 0.9 : price",
+            f.format(&get_node("qty>100?price*0.9:price"))
+        );
+
+        let mut f = Formatter::new();
+        assert_eq!(
+            "a ? b ? 1 : 2 : c ? 3 : 4",
+            f.format(&get_node("a?(b?1:2):(c?3:4)"))
+        );
+
+        let mut f = Formatter::new();
+        assert_eq!("(a ? b : c) ? 1 : 2", f.format(&get_node("(a?b:c)?1:2")));
+
+        let mut f = Formatter::new();
+        assert_eq!(
+            "(a ? 1 : 2) + -(b ? 3 : 4) * max(c ? 5 : 6)",
+            f.format(&get_node("(a?1:2)+-(b?3:4)*max(c?5:6)"))
+        );
+    }
+
+    #[test]
+    fn keep_radix_and_separator() {
+        let mut f = Formatter::new();
+        assert_eq!(
+            "0xFF + 0b1010 * 0o17 - 1_000 + 0.000_1",
+            f.format(&get_node("0xFF+0b1010*0o17-1_000+0.000_1"))
+        );
+    }
+
+    #[test]
+    fn normalize_radix() {
+        let code = "0xff + 0b1010 * 0o17 - 1_000 + 0.000_1";
+        let format = |radix| {
+            Formatter::with_options(FormatOptions { radix })
+                .format(&get_node(code))
+                .to_string()
+        };
+
+        assert_eq!("255 + 10 * 15 - 1000 + 0.000_1", format(Radix::Decimal));
+        assert_eq!(
+            "0xFF + 0xA * 0xF - 0x3E8 + 0.000_1",
+            format(Radix::Hexadecimal)
+        );
+        assert_eq!(
+            "0o377 + 0o12 * 0o17 - 0o1750 + 0.000_1",
+            format(Radix::Octal)
+        );
+        assert_eq!(
+            "0b11111111 + 0b1010 * 0b1111 - 0b1111101000 + 0.000_1",
+            format(Radix::Binary)
+        );
+        assert_eq!(
+            "0x0",
+            Formatter::with_options(FormatOptions {
+                radix: Radix::Hexadecimal
+            })
+            .format(&get_node("0"))
+        );
+    }
+
+    #[test]
+    fn keep_float_literal() {
+        let mut f = Formatter::new();
+        assert_eq!("1.50 * 2e3", f.format(&get_node("1.50*2e3")));
+    }
+
+    /// a tiny xorshift generator, to keep the crate free of dependencies
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    /// a literal in any radix or with a fraction, or a variable
+    fn random_leaf(rng: &mut Rng) -> Node {
+        const RAWS: [&str; 8] = [
+            "0xFF", "0b1010", "0o17", "1_000", "1.5", "2e3", "0.25", "1_0.5e-1",
+        ];
+        const NAMES: [&str; 3] = ["x", "y", "rate"];
+        if rng.below(4) == 0 {
+            return Node::Variable {
+                kind: IDENT,
+                name: NAMES[rng.below(NAMES.len() as u64) as usize].to_string(),
+                span: Span::default(),
+            };
+        }
+        let raw = match rng.below(3) {
+            0 => RAWS[rng.below(RAWS.len() as u64) as usize].to_string(),
+            _ => rng.below(11).to_string(),
+        };
+        Node::Literal {
+            kind: NUM,
+            value: Num::from_literal(&raw),
+            raw,
+            span: Span::default(),
+        }
+    }
+
+    fn random_unary(rng: &mut Rng, operand: Node) -> Node {
+        let (kind, op) = match rng.below(4) {
+            0 => (NEG_EXPR, MINUS),
+            1 => (NOT_EXPR, BANG),
+            2 => (BIT_NOT_EXPR, TILDE),
+            _ => (POS_EXPR, PLUS),
+        };
+        Node::Unary {
+            kind,
+            op,
+            operand: Box::new(operand),
+            span: Span::default(),
+        }
+    }
+
+    fn random_binary(rng: &mut Rng, left: Node, right: Node) -> Node {
+        let (kind, op) = match rng.below(20) {
+            0 => (ADD_EXPR, PLUS),
+            1 => (SUB_EXPR, MINUS),
+            2 => (MUL_EXPR, STAR),
+            3 => (DIV_EXPR, SLASH),
+            4 => (INT_DIV_EXPR, DOUBLE_SLASH),
+            5 => (REM_EXPR, PERCENT),
+            6 => (EQ_EXPR, EQ_EQ),
+            7 => (NE_EXPR, BANG_EQ),
+            8 => (LT_EXPR, LT),
+            9 => (LE_EXPR, LT_EQ),
+            10 => (GT_EXPR, GT),
+            11 => (GE_EXPR, GT_EQ),
+            12 => (AND_EXPR, AMP_AMP),
+            13 => (OR_EXPR, PIPE_PIPE),
+            14 => (BIT_AND_EXPR, AMP),
+            15 => (BIT_OR_EXPR, PIPE),
+            16 => (BIT_XOR_EXPR, CARET),
+            17 => (SHL_EXPR, LT_LT),
+            18 => (SHR_EXPR, GT_GT),
+            _ => (POW_EXPR, DOUBLE_STAR),
+        };
+        Node::Expr {
+            kind,
+            left: Box::new(left),
+            op,
+            right: Box::new(right),
+            span: Span::default(),
+        }
+    }
+
+    fn conditional_node(condition: Node, then: Node, otherwise: Node) -> Node {
+        Node::Conditional {
+            kind: COND_EXPR,
+            condition: Box::new(condition),
+            then: Box::new(then),
+            otherwise: Box::new(otherwise),
+            span: Span::default(),
+        }
+    }
+
+    fn call_node(rng: &mut Rng, args: Vec<Node>) -> Node {
+        Node::Call {
+            kind: CALL_EXPR,
+            name: ["max", "f"][rng.below(2) as usize].to_string(),
+            args,
+            span: Span::default(),
+        }
+    }
+
+    fn random_node(rng: &mut Rng, depth: usize) -> Node {
+        if depth == 0 || rng.below(4) == 0 {
+            return random_leaf(rng);
+        }
+        match rng.below(10) {
+            0 => {
+                let condition = random_node(rng, depth - 1);
+                let then = random_node(rng, depth - 1);
+                conditional_node(condition, then, random_node(rng, depth - 1))
+            }
+            1 => {
+                let args = (0..rng.below(4))
+                    .map(|_| random_node(rng, depth - 1))
+                    .collect();
+                call_node(rng, args)
+            }
+            2 | 3 => {
+                let operand = random_node(rng, depth - 1);
+                random_unary(rng, operand)
+            }
+            _ => {
+                let left = random_node(rng, depth - 1);
+                let right = random_node(rng, depth - 1);
+                random_binary(rng, left, right)
+            }
+        }
+    }
+
+    /// a chain nested `depth` times, every node of which has only one operand going deeper,
+    /// to get near `MAX_DEPTH` without growing exponentially
+    fn random_chain(rng: &mut Rng, depth: usize) -> Node {
+        (0..depth).fold(random_leaf(rng), |node, _| {
+            let leaf = random_leaf(rng);
+            match rng.below(8) {
+                0 => conditional_node(node, leaf, random_leaf(rng)),
+                1 => conditional_node(leaf, node, random_leaf(rng)),
+                2 => conditional_node(leaf, random_leaf(rng), node),
+                3 => call_node(rng, vec![leaf, node]),
+                4 | 5 => random_unary(rng, node),
+                6 => random_binary(rng, node, leaf),
+                _ => random_binary(rng, leaf, node),
+            }
+        })
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..500 {
+            let node = random_node(&mut rng, 5);
+            let output = Formatter::new().format(&node).to_string();
+            assert_eq!(node, get_node(&output).without_span(), "{}", output);
+        }
+        for _ in 0..50 {
+            let depth = MAX_DEPTH - rng.below(8) as usize;
+            let node = random_chain(&mut rng, depth);
+            let output = Formatter::new().format(&node).to_string();
+            assert_eq!(node, get_node(&output).without_span(), "{}", output);
+        }
+    }
+
+    #[test]
+    fn deep_round_trip() {
+        let n = MAX_DEPTH / 2;
+        for code in [
+            format!("{}x", "-".repeat(MAX_DEPTH)),
+            format!("{}1{}", "f(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH)),
+            format!("{}1{}", "1 + (".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH)),
+            format!("{}1{}", "(".repeat(n), " + 1) * 2".repeat(n)),
+            format!("{}2", "-2 ** ".repeat(n)),
+            format!(
+                "{}a{}",
+                "(".repeat(MAX_DEPTH - 1),
+                " ? 1 : 2)".repeat(MAX_DEPTH - 1)
+            ),
+        ] {
+            let node = get_node(&code);
+            let output = Formatter::new().format(&node).to_string();
+            assert_eq!(
+                node.without_span(),
+                get_node(&output).without_span(),
+                "{}",
+                output
+            );
+        }
     }
 }
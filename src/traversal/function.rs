@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::error::EvalErrorKind;
+use crate::traversal::eval::OverflowMode;
+use crate::traversal::number::Number;
+
+/// the number of arguments a function accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    /// exactly this many, e.g. `abs(x)` is `Exact(1)`
+    Exact(usize),
+    /// this many or more, e.g. `max(a, ...)` is `AtLeast(1)`
+    AtLeast(usize),
+}
+
+impl Arity {
+    pub fn accepts(self, count: usize) -> bool {
+        match self {
+            Arity::Exact(n) => count == n,
+            Arity::AtLeast(n) => count >= n,
+        }
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arity::Exact(n) => write!(f, "{}", n),
+            Arity::AtLeast(n) => write!(f, "at least {}", n),
+        }
+    }
+}
+
+/// the body of a function, which receives the evaluated arguments in order
+///
+/// the arity is checked before it is called
+type Function<N> = Box<dyn Fn(Vec<N>, OverflowMode) -> Result<N, EvalErrorKind>>;
+
+/// the named functions which can be called in a expression
+///
+/// # Example
+/// ```rust
+/// let mut registry = Registry::<i64>::with_builtins();
+/// registry.register("clamp", Arity::Exact(3), |args, _| {
+///     Ok(args[0].clamp(args[1], args[2]))
+/// });
+///
+/// let ast = build_ast("clamp(pow(2, 10), abs(-3), 100)").unwrap();
+/// assert_eq!(Ok(100), eval_with_registry(&ast, &(), &registry, EvalOptions::default()));
+/// ```
+pub struct Registry<N> {
+    functions: HashMap<String, (Arity, Function<N>)>,
+}
+
+impl<N> Registry<N> {
+    /// a registry without any function
+    pub fn new() -> Self {
+        Registry {
+            functions: HashMap::new(),
+        }
+    }
+    /// add a function, or replace the one with the same name
+    pub fn register<F>(&mut self, name: &str, arity: Arity, function: F) -> &mut Self
+    where
+        F: Fn(Vec<N>, OverflowMode) -> Result<N, EvalErrorKind> + 'static,
+    {
+        self.functions
+            .insert(name.to_string(), (arity, Box::new(function)));
+        self
+    }
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions.contains_key(name)
+    }
+    /// the arity of the function, or `None` if it is not registered
+    pub fn arity(&self, name: &str) -> Option<Arity> {
+        self.functions.get(name).map(|(arity, _)| *arity)
+    }
+    /// call the function with the arguments, which must match its arity
+    pub(crate) fn call(
+        &self,
+        name: &str,
+        args: Vec<N>,
+        overflow: OverflowMode,
+    ) -> Result<N, EvalErrorKind> {
+        match self.functions.get(name) {
+            Some((arity, function)) if arity.accepts(args.len()) => function(args, overflow),
+            Some((arity, _)) => Err(EvalErrorKind::ArityMismatch {
+                expected: *arity,
+                found: args.len(),
+            }),
+            None => Err(EvalErrorKind::UnknownFunction),
+        }
+    }
+}
+
+impl<N: Number + 'static> Registry<N> {
+    /// a registry with `abs(x)`, `min(a, ...)`, `max(a, ...)` and `pow(x, y)`,
+    /// which is the same as `x ** y`
+    pub fn with_builtins() -> Self {
+        let mut registry = Registry::new();
+        registry
+            .register("abs", Arity::Exact(1), |mut args: Vec<N>, overflow| {
+                let x = args.pop().unwrap();
+                match x < zero() {
+                    true => x.neg(overflow),
+                    false => Ok(x),
+                }
+            })
+            .register("min", Arity::AtLeast(1), |args, _| {
+                Ok(pick(args, |x, acc| x < acc))
+            })
+            .register("max", Arity::AtLeast(1), |args, _| {
+                Ok(pick(args, |x, acc| x > acc))
+            })
+            .register("pow", Arity::Exact(2), |mut args: Vec<N>, overflow| {
+                let exp = args.pop().unwrap();
+                args.pop().unwrap().pow(exp, overflow)
+            });
+        registry
+    }
+}
+
+impl<N> Default for Registry<N> {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+/// every numeric type can represent the literal `0`
+fn zero<N: Number>() -> N {
+    N::from_literal("0").unwrap()
+}
+
+/// the first argument which is preferred over all the others, the arity guarantees there is one
+fn pick<N>(args: Vec<N>, prefer: impl Fn(&N, &N) -> bool) -> N {
+    args.into_iter()
+        .reduce(|acc, x| if prefer(&x, &acc) { x } else { acc })
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arity() {
+        assert!(Arity::Exact(2).accepts(2));
+        assert!(!Arity::Exact(2).accepts(3));
+        assert!(Arity::AtLeast(1).accepts(3));
+        assert!(!Arity::AtLeast(1).accepts(0));
+        assert_eq!("at least 1", Arity::AtLeast(1).to_string());
+    }
+
+    #[test]
+    fn builtins() {
+        let registry = Registry::<i32>::with_builtins();
+        let checked = OverflowMode::Checked;
+
+        assert_eq!(Ok(3), registry.call("abs", vec![-3], checked));
+        assert_eq!(Ok(3), registry.call("abs", vec![3], checked));
+        assert_eq!(
+            Err(EvalErrorKind::Overflow),
+            registry.call("abs", vec![i32::MIN], checked)
+        );
+        assert_eq!(Ok(-2), registry.call("min", vec![4, -2, 7], checked));
+        assert_eq!(Ok(7), registry.call("max", vec![4, -2, 7], checked));
+        assert_eq!(
+            Err(EvalErrorKind::ArityMismatch {
+                expected: Arity::AtLeast(1),
+                found: 0
+            }),
+            registry.call("max", vec![], checked)
+        );
+        assert_eq!(Ok(1024), registry.call("pow", vec![2, 10], checked));
+        assert_eq!(
+            Err(EvalErrorKind::Overflow),
+            registry.call("pow", vec![2, 31], checked)
+        );
+        assert_eq!(
+            Err(EvalErrorKind::UnknownFunction),
+            registry.call("sqrt", vec![4], checked)
+        );
+    }
+}
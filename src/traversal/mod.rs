@@ -1,18 +1,120 @@
 use crate::{
+    error::EvalError,
     parser::Node,
     traversal::{eval::Executor, format::Formatter},
 };
 
+mod env;
 mod eval;
 mod format;
+mod function;
+mod number;
+mod value;
 mod visitor;
 
+pub use env::Resolver;
+pub use eval::{DivisionMode, EvalOptions, OverflowMode};
+pub use format::{FormatOptions, Radix};
+pub use function::{Arity, Registry};
+pub use number::Number;
+pub use value::{Value, ValueType};
+
 /// execute a expression expressed in AST and return its result
+///
+/// # Panics
+///
+/// panic if the evaluation fails, use `try_eval` to handle the error instead
 pub fn eval(root: &Node) -> i32 {
+    match try_eval(root) {
+        Ok(value) => value,
+        Err(err) => panic!("{}", err),
+    }
+}
+
+/// execute a expression expressed in AST
+/// return a `Result` to indicate whether it is successful or not
+/// - Ok(Value)
+/// - Err(EvalError), the arithmetic overflows or divides by zero
+pub fn try_eval(root: &Node) -> Result<i32, EvalError> {
     Executor::new().eval(root)
 }
 
+/// execute a expression expressed in AST with the numeric type `N`
+/// return a `Result` to indicate whether it is successful or not
+/// - Ok(Value)
+/// - Err(EvalError)
+pub fn eval_as<N: Number>(root: &Node, options: EvalOptions) -> Result<N, EvalError> {
+    Executor::with_options(options).eval(root)
+}
+
+/// execute a expression expressed in AST with the given options
+/// return a `Result` to indicate whether it is successful or not
+/// - Ok(Value)
+/// - Err(EvalError)
+pub fn eval_with(root: &Node, options: EvalOptions) -> Result<i32, EvalError> {
+    Executor::with_options(options).eval(root)
+}
+
+/// execute a expression expressed in AST with the numeric type `N`,
+/// and the variables are looked up in `env`
+/// return a `Result` to indicate whether it is successful or not
+/// - Ok(Value)
+/// - Err(EvalError), e.g. `EvalErrorKind::UnboundVariable` for a name which `env` does not know
+pub fn eval_with_env<N: Number>(
+    root: &Node,
+    env: &impl Resolver<N>,
+    options: EvalOptions,
+) -> Result<N, EvalError> {
+    Executor::with_env(env, options).eval(root)
+}
+
+/// execute a expression expressed in AST with the numeric type `N`,
+/// the variables are looked up in `env` and the functions in `functions`
+/// return a `Result` to indicate whether it is successful or not
+/// - Ok(Value)
+/// - Err(EvalError), e.g. `EvalErrorKind::UnknownFunction` for a name which `functions` does not know
+pub fn eval_with_registry<N: Number>(
+    root: &Node,
+    env: &impl Resolver<N>,
+    functions: &Registry<N>,
+    options: EvalOptions,
+) -> Result<N, EvalError> {
+    Executor::with_registry(env, functions, options).eval(root)
+}
+
+/// execute a expression expressed in AST with the numeric type `N`,
+/// and the variables are looked up in `env`
+/// return a `Result` to indicate whether it is successful or not
+/// - Ok(Value), a number or a boolean, e.g. `Value::Bool(true)` for `x > 1 && x < 10` with `x = 5`
+/// - Err(EvalError), e.g. `EvalErrorKind::TypeMismatch` for `!1`
+pub fn eval_value<N: Number>(
+    root: &Node,
+    env: &impl Resolver<N>,
+    options: EvalOptions,
+) -> Result<Value<N>, EvalError> {
+    Executor::with_env(env, options).eval_value(root)
+}
+
+/// execute a expression expressed in AST with floating-point arithmetic
+///
+/// integer literals are converted to `f64`, and the division never fails, e.g. `1 / 0` is `inf`
+///
+/// # Panics
+///
+/// panic if the result is not a number, e.g. `1 < 2`
+pub fn eval_f64(root: &Node) -> f64 {
+    match eval_as(root, EvalOptions::default()) {
+        Ok(value) => value,
+        Err(err) => panic!("{}", err),
+    }
+}
+
 /// format a expression expressed in AST
 pub fn format(root: &Node) -> String {
     Formatter::new().format(root).to_string()
 }
+
+/// format a expression expressed in AST with the given options
+pub fn format_with(root: &Node, options: FormatOptions) -> String {
+    Formatter::with_options(options).format(root).to_string()
+}
@@ -0,0 +1,651 @@
+use crate::bigint::BigInt;
+use crate::error::EvalErrorKind;
+use crate::rational::Rational;
+use crate::traversal::eval::{DivisionMode, OverflowMode};
+
+/// the numeric type which a expression can be evaluated with
+///
+/// every operation reports the kind of the failure,
+/// and the `Executor` attaches the span of the failing expression to it
+///
+/// # Example
+/// ```rust
+/// let ast = build_ast("3000000000 * 2").unwrap();
+/// assert_eq!(Ok(6000000000_i64), eval_as::<i64>(&ast, EvalOptions::default()));
+/// ```
+pub trait Number: Sized + PartialOrd {
+    /// parse the raw text of a number literal, e.g. `42`, `-1.5e3`
+    ///
+    /// return `None` if the literal can not be represented by the type
+    fn from_literal(raw: &str) -> Option<Self>;
+    /// convert the exact value of an integer literal, which is parsed in any radix already
+    ///
+    /// return `None` if the value can not be represented by the type
+    fn from_int(value: &BigInt) -> Option<Self> {
+        Self::from_literal(&value.to_string())
+    }
+    /// take the bits of a radix literal as the two's complement in the width of the type,
+    /// e.g. `0xFFFF_FFFF` is `-1` for `i32`, which is done only in `OverflowMode::Wrapping`
+    ///
+    /// return `None` if the literal has more bits than the width, or the type has no width
+    fn from_twos_complement(_value: &BigInt) -> Option<Self> {
+        None
+    }
+
+    fn add(self, rhs: Self, overflow: OverflowMode) -> Result<Self, EvalErrorKind>;
+    fn sub(self, rhs: Self, overflow: OverflowMode) -> Result<Self, EvalErrorKind>;
+    fn mul(self, rhs: Self, overflow: OverflowMode) -> Result<Self, EvalErrorKind>;
+    fn div(self, rhs: Self, overflow: OverflowMode) -> Result<Self, EvalErrorKind>;
+    fn neg(self, overflow: OverflowMode) -> Result<Self, EvalErrorKind>;
+    /// raise `self` to the power of `exp`, e.g. `2 ** 10`
+    fn pow(self, exp: Self, overflow: OverflowMode) -> Result<Self, EvalErrorKind>;
+    /// the integer quotient rounded according to the `mode`, e.g. `7 // 2`
+    fn int_div(
+        self,
+        rhs: Self,
+        mode: DivisionMode,
+        overflow: OverflowMode,
+    ) -> Result<Self, EvalErrorKind>;
+    /// the remainder which matches `int_div`, e.g. `7 % 2`
+    fn rem(
+        self,
+        rhs: Self,
+        mode: DivisionMode,
+        overflow: OverflowMode,
+    ) -> Result<Self, EvalErrorKind>;
+
+    /// the bitwise operators are only for the integer types,
+    /// the others report an `EvalErrorKind::UnsupportedOperation` by default
+    fn bit_and(self, _: Self) -> Result<Self, EvalErrorKind> {
+        Err(EvalErrorKind::UnsupportedOperation)
+    }
+    fn bit_or(self, _: Self) -> Result<Self, EvalErrorKind> {
+        Err(EvalErrorKind::UnsupportedOperation)
+    }
+    fn bit_xor(self, _: Self) -> Result<Self, EvalErrorKind> {
+        Err(EvalErrorKind::UnsupportedOperation)
+    }
+    fn bit_not(self) -> Result<Self, EvalErrorKind> {
+        Err(EvalErrorKind::UnsupportedOperation)
+    }
+    /// shift `self` left by `amount` bits, the bits shifted out are lost, e.g. `1 << 4`
+    ///
+    /// a negative amount, or one which is not less than the width of the type,
+    /// is an `EvalErrorKind::ShiftOutOfRange`
+    fn shl(self, _amount: Self) -> Result<Self, EvalErrorKind> {
+        Err(EvalErrorKind::UnsupportedOperation)
+    }
+    /// the arithmetic shift right, which keeps the sign, e.g. `-16 >> 2` is `-4`
+    fn shr(self, _amount: Self) -> Result<Self, EvalErrorKind> {
+        Err(EvalErrorKind::UnsupportedOperation)
+    }
+}
+
+/// implement `Number` for the primitive integers with the `checked_*`, `wrapping_*` and `saturating_*` families
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                /// only the literal without fraction and exponent is accepted
+                fn from_literal(raw: &str) -> Option<Self> {
+                    raw.parse().ok()
+                }
+                fn from_int(value: &BigInt) -> Option<Self> {
+                    value.to_i128()?.try_into().ok()
+                }
+                fn from_twos_complement(value: &BigInt) -> Option<Self> {
+                    let bits = value.to_u128()?;
+                    // shifted twice, since `i128` is as wide as `u128`
+                    match bits >> (<$t>::BITS - 1) >> 1 {
+                        0 => Some(bits as $t),
+                        _ => None,
+                    }
+                }
+                fn add(self, rhs: Self, overflow: OverflowMode) -> Result<Self, EvalErrorKind> {
+                    match overflow {
+                        OverflowMode::Checked => self.checked_add(rhs).ok_or(EvalErrorKind::Overflow),
+                        OverflowMode::Wrapping => Ok(self.wrapping_add(rhs)),
+                        OverflowMode::Saturating => Ok(self.saturating_add(rhs)),
+                    }
+                }
+                fn sub(self, rhs: Self, overflow: OverflowMode) -> Result<Self, EvalErrorKind> {
+                    match overflow {
+                        OverflowMode::Checked => self.checked_sub(rhs).ok_or(EvalErrorKind::Overflow),
+                        OverflowMode::Wrapping => Ok(self.wrapping_sub(rhs)),
+                        OverflowMode::Saturating => Ok(self.saturating_sub(rhs)),
+                    }
+                }
+                fn mul(self, rhs: Self, overflow: OverflowMode) -> Result<Self, EvalErrorKind> {
+                    match overflow {
+                        OverflowMode::Checked => self.checked_mul(rhs).ok_or(EvalErrorKind::Overflow),
+                        OverflowMode::Wrapping => Ok(self.wrapping_mul(rhs)),
+                        OverflowMode::Saturating => Ok(self.saturating_mul(rhs)),
+                    }
+                }
+                /// the division by zero is always an error, and the only overflow is `MIN / -1`
+                fn div(self, rhs: Self, overflow: OverflowMode) -> Result<Self, EvalErrorKind> {
+                    if rhs == 0 {
+                        return Err(EvalErrorKind::DivisionByZero);
+                    }
+                    match overflow {
+                        OverflowMode::Checked => {
+                            self.checked_div(rhs).ok_or(EvalErrorKind::DivisionOverflow)
+                        }
+                        OverflowMode::Wrapping => Ok(self.wrapping_div(rhs)),
+                        OverflowMode::Saturating => Ok(self.saturating_div(rhs)),
+                    }
+                }
+                /// the only overflow is `-MIN`
+                fn neg(self, overflow: OverflowMode) -> Result<Self, EvalErrorKind> {
+                    match overflow {
+                        OverflowMode::Checked => self.checked_neg().ok_or(EvalErrorKind::Overflow),
+                        OverflowMode::Wrapping => Ok(self.wrapping_neg()),
+                        OverflowMode::Saturating => Ok(self.saturating_neg()),
+                    }
+                }
+                /// exponentiation by squaring with `mul`, so the overflow is handled the same way
+                ///
+                /// a negative exponent truncates like the division, e.g. `2 ** -1` is `0`
+                fn pow(self, exp: Self, overflow: OverflowMode) -> Result<Self, EvalErrorKind> {
+                    if exp < 0 {
+                        return match self {
+                            0 => Err(EvalErrorKind::DivisionByZero),
+                            1 => Ok(1),
+                            -1 => Ok(if exp % 2 == 0 { 1 } else { -1 }),
+                            _ => Ok(0),
+                        };
+                    }
+                    let (mut base, mut exp, mut acc) = (self, exp, 1);
+                    while exp > 0 {
+                        if exp % 2 == 1 {
+                            acc = Number::mul(acc, base, overflow)?;
+                        }
+                        exp /= 2;
+                        // the square is only taken if it is needed, so that it never overflows in vain
+                        if exp > 0 {
+                            base = Number::mul(base, base, overflow)?;
+                        }
+                    }
+                    Ok(acc)
+                }
+                /// the only overflow is `MIN // -1`
+                fn int_div(
+                    self,
+                    rhs: Self,
+                    mode: DivisionMode,
+                    overflow: OverflowMode,
+                ) -> Result<Self, EvalErrorKind> {
+                    if rhs == -1 {
+                        return Number::neg(self, overflow).map_err(|_| EvalErrorKind::DivisionOverflow);
+                    }
+                    int_div_rem(self, rhs, mode).map(|(quotient, _)| quotient)
+                }
+                /// `MIN % -1` is `0`, which never overflows
+                fn rem(self, rhs: Self, mode: DivisionMode, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+                    if rhs == -1 {
+                        return Ok(0);
+                    }
+                    int_div_rem(self, rhs, mode).map(|(_, remainder)| remainder)
+                }
+                fn bit_and(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+                    Ok(self & rhs)
+                }
+                fn bit_or(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+                    Ok(self | rhs)
+                }
+                fn bit_xor(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+                    Ok(self ^ rhs)
+                }
+                fn bit_not(self) -> Result<Self, EvalErrorKind> {
+                    Ok(!self)
+                }
+                fn shl(self, amount: Self) -> Result<Self, EvalErrorKind> {
+                    u32::try_from(amount)
+                        .ok()
+                        .and_then(|amount| self.checked_shl(amount))
+                        .ok_or(EvalErrorKind::ShiftOutOfRange)
+                }
+                fn shr(self, amount: Self) -> Result<Self, EvalErrorKind> {
+                    u32::try_from(amount)
+                        .ok()
+                        .and_then(|amount| self.checked_shr(amount))
+                        .ok_or(EvalErrorKind::ShiftOutOfRange)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i32, i64, i128);
+
+/// floating-point arithmetic following IEEE 754, which never fails,
+/// e.g. `1 / 0` is `inf` and `0 / 0` is `NaN`
+impl Number for f64 {
+    fn from_literal(raw: &str) -> Option<Self> {
+        raw.parse().ok()
+    }
+    fn add(self, rhs: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(self + rhs)
+    }
+    fn sub(self, rhs: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(self - rhs)
+    }
+    fn mul(self, rhs: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(self * rhs)
+    }
+    fn div(self, rhs: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(self / rhs)
+    }
+    fn neg(self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(-self)
+    }
+    fn pow(self, exp: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(self.powf(exp))
+    }
+    fn int_div(
+        self,
+        rhs: Self,
+        mode: DivisionMode,
+        _: OverflowMode,
+    ) -> Result<Self, EvalErrorKind> {
+        Ok(match mode {
+            DivisionMode::Truncated => (self / rhs).trunc(),
+            DivisionMode::Floored => (self / rhs).floor(),
+            DivisionMode::Euclidean => self.div_euclid(rhs),
+        })
+    }
+    fn rem(self, rhs: Self, mode: DivisionMode, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(match mode {
+            DivisionMode::Truncated => self % rhs,
+            DivisionMode::Floored => match self % rhs {
+                r if r != 0.0 && (r < 0.0) != (rhs < 0.0) => r + rhs,
+                r => r,
+            },
+            DivisionMode::Euclidean => self.rem_euclid(rhs),
+        })
+    }
+}
+
+/// exact integer arithmetic, which never overflows
+impl Number for BigInt {
+    /// only the literal without fraction and exponent is accepted
+    fn from_literal(raw: &str) -> Option<Self> {
+        raw.parse().ok()
+    }
+    fn from_int(value: &BigInt) -> Option<Self> {
+        Some(value.clone())
+    }
+    fn add(self, rhs: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(self + rhs)
+    }
+    fn sub(self, rhs: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(self - rhs)
+    }
+    fn mul(self, rhs: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(self * rhs)
+    }
+    fn div(self, rhs: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        self.checked_div(&rhs).ok_or(EvalErrorKind::DivisionByZero)
+    }
+    fn neg(self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(-self)
+    }
+    /// a negative exponent truncates like the division, e.g. `2 ** -1` is `0`,
+    /// and a result of more than `BigInt::MAX_BITS` bits is an `EvalErrorKind::ExponentTooLarge`
+    fn pow(self, exp: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        let one = BigInt::from(1);
+        if exp.is_negative() {
+            let odd = !exp.checked_rem(&BigInt::from(2)).unwrap().is_zero();
+            return match self {
+                x if x.is_zero() => Err(EvalErrorKind::DivisionByZero),
+                x if x.clone().abs() != one => Ok(BigInt::zero()),
+                x if x.is_negative() && odd => Ok(x),
+                _ => Ok(one),
+            };
+        }
+        check_pow_bits(self.log2_lower(), &exp)?;
+        Ok(pow_by_squaring(self, exp, one))
+    }
+    fn int_div(
+        self,
+        rhs: Self,
+        mode: DivisionMode,
+        _: OverflowMode,
+    ) -> Result<Self, EvalErrorKind> {
+        int_div_rem(self, rhs, mode).map(|(quotient, _)| quotient)
+    }
+    fn rem(self, rhs: Self, mode: DivisionMode, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        int_div_rem(self, rhs, mode).map(|(_, remainder)| remainder)
+    }
+    fn bit_and(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        Ok(self & rhs)
+    }
+    fn bit_or(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        Ok(self | rhs)
+    }
+    fn bit_xor(self, rhs: Self) -> Result<Self, EvalErrorKind> {
+        Ok(self ^ rhs)
+    }
+    fn bit_not(self) -> Result<Self, EvalErrorKind> {
+        Ok(!self)
+    }
+    /// there is no width to exceed, but like `pow` the result is limited to `BigInt::MAX_BITS` bits
+    fn shl(self, amount: Self) -> Result<Self, EvalErrorKind> {
+        amount
+            .to_u32()
+            .and_then(|amount| self.checked_shl(amount))
+            .ok_or(EvalErrorKind::ShiftOutOfRange)
+    }
+    fn shr(self, amount: Self) -> Result<Self, EvalErrorKind> {
+        amount
+            .to_u32()
+            .map(|amount| self >> amount)
+            .ok_or(EvalErrorKind::ShiftOutOfRange)
+    }
+}
+
+/// exact fraction arithmetic, which never overflows,
+/// and the decimal literal is parsed exactly, e.g. `0.1` is `1/10`
+impl Number for Rational {
+    fn from_literal(raw: &str) -> Option<Self> {
+        Rational::from_literal(raw)
+    }
+    fn from_int(value: &BigInt) -> Option<Self> {
+        Some(Rational::from(value.clone()))
+    }
+    fn add(self, rhs: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(self + rhs)
+    }
+    fn sub(self, rhs: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(self - rhs)
+    }
+    fn mul(self, rhs: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(self * rhs)
+    }
+    fn div(self, rhs: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        self.checked_div(rhs).ok_or(EvalErrorKind::DivisionByZero)
+    }
+    fn neg(self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        Ok(-self)
+    }
+    /// only an integer exponent is accepted, since the result may be irrational, e.g. `2 ** 0.5`,
+    /// and like `BigInt` the numerator and the denominator are limited to `BigInt::MAX_BITS` bits
+    fn pow(self, exp: Self, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        if !exp.is_integer() {
+            return Err(EvalErrorKind::NonIntegerExponent);
+        }
+        let exp = exp.numer().clone();
+        let log2 = self.numer().log2_lower().max(self.denom().log2_lower());
+        check_pow_bits(log2, &exp.clone().abs())?;
+        match exp.is_negative() {
+            true => Rational::from(1)
+                .checked_div(pow_by_squaring(self, exp.abs(), Rational::from(1)))
+                .ok_or(EvalErrorKind::DivisionByZero),
+            false => Ok(pow_by_squaring(self, exp, Rational::from(1))),
+        }
+    }
+    fn int_div(
+        self,
+        rhs: Self,
+        mode: DivisionMode,
+        _: OverflowMode,
+    ) -> Result<Self, EvalErrorKind> {
+        int_div_rem(self, rhs, mode).map(|(quotient, _)| quotient)
+    }
+    /// the remainder is exact too, e.g. `7.5 % 2` is `3/2`
+    fn rem(self, rhs: Self, mode: DivisionMode, _: OverflowMode) -> Result<Self, EvalErrorKind> {
+        int_div_rem(self, rhs, mode).map(|(_, remainder)| remainder)
+    }
+}
+
+/// the exact numeric types which provide the truncated quotient and remainder
+trait TruncDivRem:
+    Sized + Clone + PartialOrd + std::ops::Add<Output = Self> + std::ops::Sub<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    /// `None` if `rhs` is zero
+    fn trunc_div_rem(self, rhs: &Self) -> Option<(Self, Self)>;
+}
+
+/// the caller must exclude `MIN / -1`, which is the only overflow
+macro_rules! impl_trunc_div_rem {
+    ($($t:ty),*) => {
+        $(
+            impl TruncDivRem for $t {
+                fn zero() -> Self {
+                    0
+                }
+                fn one() -> Self {
+                    1
+                }
+                fn trunc_div_rem(self, rhs: &Self) -> Option<(Self, Self)> {
+                    Some((self.checked_div(*rhs)?, self.checked_rem(*rhs)?))
+                }
+            }
+        )*
+    };
+}
+
+impl_trunc_div_rem!(i32, i64, i128);
+
+impl TruncDivRem for BigInt {
+    fn zero() -> Self {
+        BigInt::zero()
+    }
+    fn one() -> Self {
+        BigInt::from(1)
+    }
+    fn trunc_div_rem(self, rhs: &Self) -> Option<(Self, Self)> {
+        self.checked_div_rem(rhs)
+    }
+}
+
+impl TruncDivRem for Rational {
+    fn zero() -> Self {
+        Rational::from(0)
+    }
+    fn one() -> Self {
+        Rational::from(1)
+    }
+    /// the quotient is an integer, and the remainder is what is left, e.g. `7/2 = 3 * 1 + 1/2`
+    fn trunc_div_rem(self, rhs: &Self) -> Option<(Self, Self)> {
+        let quotient = Rational::from(self.clone().checked_div(rhs.clone())?.trunc());
+        let remainder = self - quotient.clone() * rhs.clone();
+        Some((quotient, remainder))
+    }
+}
+
+/// the quotient and the remainder of the `mode`, adjusted from the truncated ones
+///
+/// the floored and the euclidean ones differ only if the remainder is negative and so is `rhs`
+fn int_div_rem<N: TruncDivRem>(
+    lhs: N,
+    rhs: N,
+    mode: DivisionMode,
+) -> Result<(N, N), EvalErrorKind> {
+    let (quotient, remainder) = lhs
+        .trunc_div_rem(&rhs)
+        .ok_or(EvalErrorKind::DivisionByZero)?;
+    let adjust = match mode {
+        DivisionMode::Truncated => false,
+        DivisionMode::Floored => {
+            remainder != N::zero() && (remainder < N::zero()) != (rhs < N::zero())
+        }
+        DivisionMode::Euclidean => remainder < N::zero(),
+    };
+    Ok(match adjust {
+        false => (quotient, remainder),
+        true if mode == DivisionMode::Euclidean && rhs < N::zero() => {
+            (quotient + N::one(), remainder - rhs)
+        }
+        true => (quotient - N::one(), remainder + rhs),
+    })
+}
+
+/// reject the power of a base of at least `2^log2` which surely has more than `BigInt::MAX_BITS` bits
+fn check_pow_bits(log2: u64, exp: &BigInt) -> Result<(), EvalErrorKind> {
+    match log2 > 0 && *exp > BigInt::from((BigInt::MAX_BITS / log2) as i64) {
+        true => Err(EvalErrorKind::ExponentTooLarge),
+        false => Ok(()),
+    }
+}
+
+/// exponentiation by squaring for the types which never overflow, `exp` must not be negative
+fn pow_by_squaring<N>(mut base: N, mut exp: BigInt, one: N) -> N
+where
+    N: Clone + std::ops::Mul<Output = N>,
+{
+    let two = BigInt::from(2);
+    let mut acc = one;
+    while !exp.is_zero() {
+        let (quotient, remainder) = exp.checked_div_rem(&two).unwrap();
+        if !remainder.is_zero() {
+            acc = acc * base.clone();
+        }
+        exp = quotient;
+        if !exp.is_zero() {
+            base = base.clone() * base;
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_from_literal() {
+        assert_eq!(Some(-42), i32::from_literal("-42"));
+        assert_eq!(None, i32::from_literal("3000000000"));
+        assert_eq!(Some(3000000000), i64::from_literal("3000000000"));
+        assert_eq!(None, i64::from_literal("1.5"));
+        assert_eq!(None, i128::from_literal("1e3"));
+    }
+
+    #[test]
+    fn integer_from_int() {
+        assert_eq!(Some(i32::MIN), i32::from_int(&BigInt::from(i32::MIN)));
+        assert_eq!(None, i32::from_int(&BigInt::from(i32::MAX as i64 + 1)));
+        assert_eq!(Some(i128::MAX), i128::from_int(&BigInt::from(i128::MAX)));
+        assert_eq!(
+            None,
+            i128::from_int(&(BigInt::from(i128::MIN) - BigInt::from(1)))
+        );
+        assert_eq!(
+            Some(-1e20),
+            f64::from_int(&BigInt::from(-100_000_000_000_000_000_000_i128))
+        );
+    }
+
+    #[test]
+    fn integer_from_twos_complement() {
+        assert_eq!(
+            Some(-1),
+            i32::from_twos_complement(&BigInt::from(u32::MAX as i64))
+        );
+        assert_eq!(
+            Some(i32::MIN),
+            i32::from_twos_complement(&BigInt::from(1_i64 << 31))
+        );
+        assert_eq!(None, i32::from_twos_complement(&BigInt::from(1_i64 << 32)));
+        assert_eq!(
+            Some(-1),
+            i128::from_twos_complement(&BigInt::from_str_radix(&"F".repeat(32), 16).unwrap())
+        );
+        assert_eq!(
+            None,
+            i128::from_twos_complement(&BigInt::from_str_radix(&"F".repeat(33), 16).unwrap())
+        );
+        assert_eq!(None, f64::from_twos_complement(&BigInt::from(1)));
+    }
+
+    #[test]
+    fn float_from_literal() {
+        assert_eq!(Some(42.0), f64::from_literal("42"));
+        assert_eq!(Some(-1500.0), f64::from_literal("-1.5e3"));
+    }
+
+    #[test]
+    fn integer_overflow() {
+        let checked = OverflowMode::Checked;
+        assert_eq!(Err(EvalErrorKind::Overflow), i64::MAX.add(1, checked));
+        assert_eq!(Err(EvalErrorKind::Overflow), i128::MIN.sub(1, checked));
+        assert_eq!(
+            Err(EvalErrorKind::DivisionOverflow),
+            i64::MIN.div(-1, checked)
+        );
+        assert_eq!(Err(EvalErrorKind::DivisionByZero), 1_i128.div(0, checked));
+
+        assert_eq!(Ok(i64::MIN), i64::MAX.add(1, OverflowMode::Wrapping));
+        assert_eq!(Ok(i128::MAX), i128::MAX.mul(2, OverflowMode::Saturating));
+    }
+
+    #[test]
+    fn exponent_too_large() {
+        let checked = OverflowMode::Checked;
+        let max = BigInt::MAX_BITS as i64;
+        assert!(BigInt::from(2).pow(BigInt::from(max), checked).is_ok());
+        assert_eq!(
+            Err(EvalErrorKind::ExponentTooLarge),
+            BigInt::from(2).pow(BigInt::from(max + 1), checked)
+        );
+        assert_eq!(
+            Err(EvalErrorKind::ExponentTooLarge),
+            BigInt::from(2).pow(BigInt::from(4_000_000_000_i64), checked)
+        );
+        // the size of the base counts, e.g. `(2 ** 1000) ** 1000`
+        let base = BigInt::from(2).pow(BigInt::from(1000), checked).unwrap();
+        assert_eq!(
+            Err(EvalErrorKind::ExponentTooLarge),
+            base.pow(BigInt::from(1000), checked)
+        );
+        // the result of a base of 0, 1 or -1 never grows
+        assert_eq!(
+            Ok(BigInt::from(-1)),
+            BigInt::from(-1).pow(BigInt::from(4_000_000_001_i64), checked)
+        );
+        assert_eq!(
+            Err(EvalErrorKind::ExponentTooLarge),
+            Rational::from(2).pow(Rational::from(4_000_000_000_i64), checked)
+        );
+        assert_eq!(
+            Err(EvalErrorKind::ExponentTooLarge),
+            Rational::new(BigInt::from(1), BigInt::from(3))
+                .unwrap()
+                .pow(Rational::from(-4_000_000_000_i64), checked)
+        );
+    }
+
+    #[test]
+    fn shift() {
+        assert_eq!(Ok(16), 1_i32.shl(4));
+        assert_eq!(Ok(i32::MIN), 1_i32.shl(31));
+        assert_eq!(Ok(-4), (-16_i32).shr(2));
+        assert_eq!(Err(EvalErrorKind::ShiftOutOfRange), 1_i32.shl(32));
+        assert_eq!(Err(EvalErrorKind::ShiftOutOfRange), 1_i64.shr(-1));
+        assert_eq!(Ok(1 << 40), 1_i64.shl(40));
+        assert_eq!(
+            Ok(BigInt::from(1_i128 << 100)),
+            BigInt::from(1).shl(BigInt::from(100))
+        );
+        assert_eq!(
+            Err(EvalErrorKind::ShiftOutOfRange),
+            BigInt::from(1).shl(BigInt::from(-1))
+        );
+        assert_eq!(
+            Err(EvalErrorKind::ShiftOutOfRange),
+            BigInt::from(1).shl(BigInt::from(BigInt::MAX_BITS as i64 + 1))
+        );
+        assert_eq!(
+            Err(EvalErrorKind::ShiftOutOfRange),
+            BigInt::from(1).shl(BigInt::from(4_000_000_000_i64))
+        );
+        assert_eq!(Err(EvalErrorKind::UnsupportedOperation), 1.0.bit_and(1.0));
+        assert_eq!(
+            Err(EvalErrorKind::UnsupportedOperation),
+            Rational::from(1).shl(Rational::from(1))
+        );
+    }
+}
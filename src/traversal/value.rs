@@ -0,0 +1,49 @@
+use std::fmt;
+
+/// the result of a expression, which is a number or a boolean, e.g. `1 + 2` or `1 < 2`
+///
+/// # Example
+/// ```rust
+/// let ast = build_ast("(a + 1) * 2 >= 10 && b != 0").unwrap();
+/// let env = HashMap::from([("a", 4), ("b", 1)]);
+/// assert_eq!(Ok(Value::Bool(true)), eval_value(&ast, &env, EvalOptions::default()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value<N> {
+    Num(N),
+    Bool(bool),
+}
+
+/// the type of a `Value`, which is reported when a operator is applied to the wrong one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    Number,
+    Bool,
+}
+
+impl<N> Value<N> {
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Value::Num(_) => ValueType::Number,
+            Value::Bool(_) => ValueType::Bool,
+        }
+    }
+}
+
+impl<N: fmt::Display> fmt::Display for Value<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Num(value) => write!(f, "{}", value),
+            Value::Bool(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueType::Number => write!(f, "number"),
+            ValueType::Bool => write!(f, "boolean"),
+        }
+    }
+}
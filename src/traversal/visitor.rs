@@ -1,4 +1,5 @@
-use crate::parser::Node;
+use crate::parser::{Node, Num};
+use crate::span::Span;
 
 /// Visitor trait
 ///
@@ -7,15 +8,49 @@ pub(crate) trait Visitor<T> {
     /// data distribution of different node types is implemented by default
     fn visit(&mut self, node: &Node) -> T {
         match node {
-            Node::Literal { value, raw, .. } => self.visit_num(*value, raw),
+            Node::Literal {
+                value, raw, span, ..
+            } => self.visit_num(value, raw, *span),
+            Node::Variable { name, span, .. } => self.visit_variable(name, *span),
+            Node::Call {
+                name, args, span, ..
+            } => self.visit_call(name, args, *span),
+            Node::Unary {
+                op, operand, span, ..
+            } => self.visit_unary(op.into_str(), operand, *span),
             Node::Expr {
-                left, op, right, ..
-            } => self.visit_expr(left, op.into_str(), right),
+                left,
+                op,
+                right,
+                span,
+                ..
+            } => self.visit_expr(left, op.into_str(), right, *span),
+            Node::Conditional {
+                condition,
+                then,
+                otherwise,
+                span,
+                ..
+            } => self.visit_conditional(condition, then, otherwise, *span),
         }
     }
 
     /// customize the behavior of accessing Literal nodes
-    fn visit_num(&mut self, value: i32, raw: &str) -> T;
+    fn visit_num(&mut self, value: &Num, raw: &str, span: Span) -> T;
+    /// customize the behavior of accessing Variable nodes
+    fn visit_variable(&mut self, name: &str, span: Span) -> T;
+    /// customize the behavior of accessing Call nodes
+    fn visit_call(&mut self, name: &str, args: &[Node], span: Span) -> T;
+    /// customize the behavior of accessing Unary nodes
+    fn visit_unary(&mut self, op: &str, operand: &Node, span: Span) -> T;
     /// customize the behavior of accessing Expression nodes
-    fn visit_expr(&mut self, left: &Node, op: &str, right: &Node) -> T;
+    fn visit_expr(&mut self, left: &Node, op: &str, right: &Node, span: Span) -> T;
+    /// customize the behavior of accessing Conditional nodes
+    fn visit_conditional(
+        &mut self,
+        condition: &Node,
+        then: &Node,
+        otherwise: &Node,
+        span: Span,
+    ) -> T;
 }